@@ -0,0 +1,97 @@
+//! Run several xdotool commands in a single xdotool process.
+//!
+//! xdotool executes chained commands one after another without spawning a new process for each of them.
+//! This avoids the latency between commands, e.g. when a key has to be held for an exact period of time.
+
+use std::fmt;
+use std::process::Output;
+
+use crate::command::Command;
+use crate::shell;
+
+/// A sequence of commands and their arguments executed by a single xdotool process.
+///
+/// Note that some commands like `type` consume all remaining arguments and therefore have to be the last command in a chain.
+///
+/// # Examples
+///
+/// Hold the super key for 100ms:
+///
+/// ```no_run
+/// # use xdotool::chain::Chain;
+/// # use xdotool::command::{sub_commands, Command};
+/// # use xdotool::OptionVec;
+/// let output = Chain::new()
+///     .then(Command::Keyboard(sub_commands::Keyboard::KeyDown(OptionVec::new())), "super")
+///     .then(Command::Misc(sub_commands::Misc::Sleep), "0.1")
+///     .then(Command::Keyboard(sub_commands::Keyboard::KeyUp(OptionVec::new())), "super")
+///     .run();
+/// ```
+#[derive(Default)]
+pub struct Chain(Vec<(Command, String)>);
+
+impl Chain {
+    pub fn new() -> Chain {
+        Chain(Vec::new())
+    }
+
+    /// Append a command with its arguments to the chain.
+    pub fn then(mut self, command: Command, args: &str) -> Chain {
+        self.0.push((command, args.to_owned()));
+        self
+    }
+
+    /// Execute all commands of the chain in a single xdotool process.
+    pub fn run(&self) -> Output {
+        shell(&format!("xdotool {}", self))
+    }
+}
+
+impl fmt::Display for Chain {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let commands: Vec<String> = self
+            .0
+            .iter()
+            .map(|(command, args)| format!("{} {}", command, args))
+            .collect();
+
+        write!(f, "{}", commands.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Chain;
+    use crate::command::options::{KeyDownUpOption, SyncOption};
+    use crate::command::{sub_commands, Command};
+    use crate::optionvec::OptionVec;
+
+    #[test]
+    fn empty_chain_renders_nothing() {
+        assert_eq!(Chain::new().to_string(), "");
+    }
+
+    #[test]
+    fn commands_render_in_order_with_their_arguments() {
+        let chain = Chain::new()
+            .then(
+                Command::Keyboard(sub_commands::Keyboard::KeyDown(OptionVec(vec![
+                    KeyDownUpOption::ClearModifiers,
+                ]))),
+                "super",
+            )
+            .then(Command::Misc(sub_commands::Misc::Sleep), "0.1")
+            .then(
+                Command::Window(sub_commands::Window::WindowFocus(OptionVec(vec![
+                    SyncOption::Sync,
+                ]))),
+                "%1",
+            )
+            .then(Command::Window(sub_commands::Window::GetWindowName), "%@");
+
+        assert_eq!(
+            chain.to_string(),
+            "keydown --clearmodifiers super sleep 0.1 windowfocus --sync %1 getwindowname %@"
+        );
+    }
+}
@@ -3,6 +3,7 @@ use std::fmt;
 
 // TODO: Check if Command is even necessary or sub_command sufficies
 
+#[derive(Debug, Clone)]
 pub enum Command {
     Keyboard(sub_commands::Keyboard),
     Mouse(sub_commands::Mouse),
@@ -13,7 +14,7 @@ pub enum Command {
 
 impl fmt::Display for Command {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match &*self {
+        match self {
             Command::Keyboard(sub) => write!(f, "{}", sub),
             Command::Mouse(sub) => write!(f, "{}", sub),
             Command::Window(sub) => write!(f, "{}", sub),
@@ -26,17 +27,17 @@ impl fmt::Display for Command {
 pub mod sub_commands {
     use super::OptionVec;
 
-    #[derive(Debug)]
+    #[derive(Debug, Clone)]
     pub enum Keyboard {
-        Key(OptionVec<super::options::KeyboardOption>),
-        KeyDown(OptionVec<super::options::KeyboardOption>),
-        KeyUp(OptionVec<super::options::KeyboardOption>),
-        Type(OptionVec<super::options::KeyboardOption>),
+        Key(OptionVec<super::options::KeyOption>),
+        KeyDown(OptionVec<super::options::KeyDownUpOption>),
+        KeyUp(OptionVec<super::options::KeyDownUpOption>),
+        Type(OptionVec<super::options::TypeOption>),
     }
 
     impl super::fmt::Display for Keyboard {
         fn fmt(&self, f: &mut super::fmt::Formatter) -> super::fmt::Result {
-            match &*self {
+            match self {
                 Keyboard::Key(o) => write!(f, "key {}", o),
                 Keyboard::KeyDown(o) => write!(f, "keydown {}", o),
                 Keyboard::KeyUp(o) => write!(f, "keyup {}", o),
@@ -45,7 +46,7 @@ pub mod sub_commands {
         }
     }
 
-    #[derive(Debug)]
+    #[derive(Debug, Clone)]
     pub enum Mouse {
         MouseMove(OptionVec<super::options::MouseMoveOption>),
         MouseMoveRelative(OptionVec<super::options::MouseMoveRelativeOption>),
@@ -58,7 +59,7 @@ pub mod sub_commands {
 
     impl super::fmt::Display for Mouse {
         fn fmt(&self, f: &mut super::fmt::Formatter) -> super::fmt::Result {
-            match &*self {
+            match self {
                 Mouse::MouseMove(o) => write!(f, "mousemove {}", o),
                 Mouse::MouseMoveRelative(o) => write!(f, "mousemove_relative {}", o),
                 Mouse::Click(o) => write!(f, "click {}", o),
@@ -70,13 +71,14 @@ pub mod sub_commands {
         }
     }
 
-    #[derive(Debug)]
+    #[derive(Debug, Clone)]
     pub enum Window {
         Search(OptionVec<super::options::SearchOption>),
         SelectWindow,
         Behave,
         GetWindowPid,
         GetWindowName,
+        GetWindowClassName,
         GetWindowGeometry(OptionVec<super::options::GetWindowGeometryOption>),
         GetWindowFocus,
         WindowSize(OptionVec<super::options::WindowSizeOption>),
@@ -87,19 +89,22 @@ pub mod sub_commands {
         WindowRaise,
         WindowReparent,
         WindowClose,
+        WindowQuit,
         WindowKill,
         WindowUnmap(OptionVec<super::options::SyncOption>),
+        WindowState(OptionVec<super::options::WindowStateOption>),
         SetWindow(OptionVec<super::options::SetWindowOption>),
     }
 
     impl super::fmt::Display for Window {
         fn fmt(&self, f: &mut super::fmt::Formatter) -> super::fmt::Result {
-            match &*self {
+            match self {
                 Window::Search(o) => write!(f, "search {}", o),
                 Window::SelectWindow => write!(f, "selectwindow"),
                 Window::Behave => write!(f, "behave"),
                 Window::GetWindowPid => write!(f, "getwindowpid"),
                 Window::GetWindowName => write!(f, "getwindowname"),
+                Window::GetWindowClassName => write!(f, "getwindowclassname"),
                 Window::GetWindowGeometry(o) => write!(f, "getwindowgeometry {}", o),
                 Window::GetWindowFocus => write!(f, "getwindowfocus"),
                 Window::WindowSize(o) => write!(f, "windowsize {}", o),
@@ -110,14 +115,16 @@ pub mod sub_commands {
                 Window::WindowRaise => write!(f, "windowraise"),
                 Window::WindowReparent => write!(f, "windowreparent"),
                 Window::WindowClose => write!(f, "windowclose"),
+                Window::WindowQuit => write!(f, "windowquit"),
                 Window::WindowKill => write!(f, "windowkill"),
                 Window::WindowUnmap(o) => write!(f, "windowunmap {}", o),
+                Window::WindowState(o) => write!(f, "windowstate {}", o),
                 Window::SetWindow(o) => write!(f, "set_window {}", o),
             }
         }
     }
 
-    #[derive(Debug)]
+    #[derive(Debug, Clone)]
     pub enum Desktop {
         WindowActivate(OptionVec<super::options::SyncOption>),
         GetActiveWindow,
@@ -133,7 +140,7 @@ pub mod sub_commands {
 
     impl super::fmt::Display for Desktop {
         fn fmt(&self, f: &mut super::fmt::Formatter) -> super::fmt::Result {
-            match &*self {
+            match self {
                 Desktop::WindowActivate(o) => write!(f, "windowactivate {}", o),
                 Desktop::GetActiveWindow => write!(f, "getactivewindow"),
                 Desktop::SetNumDesktops => write!(f, "set_num_desktop"),
@@ -148,15 +155,15 @@ pub mod sub_commands {
         }
     }
 
-    #[derive(Debug)]
+    #[derive(Debug, Clone)]
     pub enum Misc {
-        Exec(OptionVec<super::options::SyncOption>),
+        Exec(OptionVec<super::options::ExecOption>),
         Sleep,
     }
 
     impl super::fmt::Display for Misc {
         fn fmt(&self, f: &mut super::fmt::Formatter) -> super::fmt::Result {
-            match &*self {
+            match self {
                 Misc::Exec(o) => write!(f, "exec {}", o),
                 Misc::Sleep => write!(f, "sleep"),
             }
@@ -165,24 +172,71 @@ pub mod sub_commands {
 }
 
 pub mod options {
-    #[derive(Debug)]
-    pub enum KeyboardOption {
+    /// Options of the `key` command.
+    ///
+    /// A window may also be a reference to the window stack of a chain, like `%1` or `%@`.
+    #[derive(Debug, Clone)]
+    pub enum KeyOption {
+        Window(String),
+        ClearModifiers,
+        Delay(u32),
+        Repeat(u32),
+        RepeatDelay(u32),
+    }
+
+    impl super::fmt::Display for KeyOption {
+        fn fmt(&self, f: &mut super::fmt::Formatter) -> super::fmt::Result {
+            match self {
+                KeyOption::Window(x) => write!(f, "--window {}", x),
+                KeyOption::ClearModifiers => write!(f, "--clearmodifiers"),
+                KeyOption::Delay(x) => write!(f, "--delay {}", x),
+                KeyOption::Repeat(x) => write!(f, "--repeat {}", x),
+                KeyOption::RepeatDelay(x) => write!(f, "--repeat-delay {}", x),
+            }
+        }
+    }
+
+    /// Options of the `keydown` and `keyup` commands.
+    ///
+    /// A window may also be a reference to the window stack of a chain, like `%1` or `%@`.
+    #[derive(Debug, Clone)]
+    pub enum KeyDownUpOption {
+        Window(String),
+        ClearModifiers,
+        Delay(u32),
+    }
+
+    impl super::fmt::Display for KeyDownUpOption {
+        fn fmt(&self, f: &mut super::fmt::Formatter) -> super::fmt::Result {
+            match self {
+                KeyDownUpOption::Window(x) => write!(f, "--window {}", x),
+                KeyDownUpOption::ClearModifiers => write!(f, "--clearmodifiers"),
+                KeyDownUpOption::Delay(x) => write!(f, "--delay {}", x),
+            }
+        }
+    }
+
+    /// Options of the `type` command.
+    ///
+    /// A window may also be a reference to the window stack of a chain, like `%1` or `%@`.
+    #[derive(Debug, Clone)]
+    pub enum TypeOption {
         Window(String),
         ClearModifiers,
         Delay(u32),
     }
 
-    impl super::fmt::Display for KeyboardOption {
+    impl super::fmt::Display for TypeOption {
         fn fmt(&self, f: &mut super::fmt::Formatter) -> super::fmt::Result {
-            match &*self {
-                KeyboardOption::Window(x) => write!(f, "--window {}", x),
-                KeyboardOption::ClearModifiers => write!(f, "--clearmodifiers"),
-                KeyboardOption::Delay(x) => write!(f, "--delay {}", x),
+            match self {
+                TypeOption::Window(x) => write!(f, "--window {}", x),
+                TypeOption::ClearModifiers => write!(f, "--clearmodifiers"),
+                TypeOption::Delay(x) => write!(f, "--delay {}", x),
             }
         }
     }
 
-    #[derive(Debug)]
+    #[derive(Debug, Clone)]
     pub enum MouseMoveOption {
         Window(String),
         Screen(u8),
@@ -193,7 +247,7 @@ pub mod options {
 
     impl super::fmt::Display for MouseMoveOption {
         fn fmt(&self, f: &mut super::fmt::Formatter) -> super::fmt::Result {
-            match &*self {
+            match self {
                 MouseMoveOption::Window(x) => write!(f, "--window {}", x),
                 MouseMoveOption::Screen(x) => write!(f, "--screen {}", x),
                 MouseMoveOption::Polar => write!(f, "--polar"),
@@ -203,7 +257,7 @@ pub mod options {
         }
     }
 
-    #[derive(Debug)]
+    #[derive(Debug, Clone)]
     pub enum MouseMoveRelativeOption {
         Polar,
         Sync,
@@ -212,7 +266,7 @@ pub mod options {
 
     impl super::fmt::Display for MouseMoveRelativeOption {
         fn fmt(&self, f: &mut super::fmt::Formatter) -> super::fmt::Result {
-            match &*self {
+            match self {
                 MouseMoveRelativeOption::Polar => write!(f, "--polar"),
                 MouseMoveRelativeOption::ClearModifiers => write!(f, "--clearmodifiers"),
                 MouseMoveRelativeOption::Sync => write!(f, "--sync"),
@@ -220,7 +274,7 @@ pub mod options {
         }
     }
 
-    #[derive(Debug)]
+    #[derive(Debug, Clone)]
     pub enum ClickOption {
         ClearModifiers,
         Repeat(u32),
@@ -230,7 +284,7 @@ pub mod options {
 
     impl super::fmt::Display for ClickOption {
         fn fmt(&self, f: &mut super::fmt::Formatter) -> super::fmt::Result {
-            match &*self {
+            match self {
                 ClickOption::ClearModifiers => write!(f, "--clearmodifiers"),
                 ClickOption::Repeat(x) => write!(f, "--repeat {}", x),
                 ClickOption::Delay(x) => write!(f, "--delay {}", x),
@@ -239,7 +293,7 @@ pub mod options {
         }
     }
 
-    #[derive(Debug)]
+    #[derive(Debug, Clone)]
     pub enum BehaveScreenEdgeOption {
         Delay(u32),
         Quiesce(u32),
@@ -247,19 +301,21 @@ pub mod options {
 
     impl super::fmt::Display for BehaveScreenEdgeOption {
         fn fmt(&self, f: &mut super::fmt::Formatter) -> super::fmt::Result {
-            match &*self {
+            match self {
                 BehaveScreenEdgeOption::Delay(x) => write!(f, "--delay {}", x),
                 BehaveScreenEdgeOption::Quiesce(x) => write!(f, "--quiesce {}", x),
             }
         }
     }
 
-    #[derive(Debug)]
+    #[derive(Debug, Clone)]
     pub enum SearchOption {
         Class,
         ClassName,
         MaxDepth(i32),
         Name,
+        Role,
+        Title,
         OnlyVisible,
         Pid(u32),
         Screen(u8),
@@ -272,11 +328,13 @@ pub mod options {
 
     impl super::fmt::Display for SearchOption {
         fn fmt(&self, f: &mut super::fmt::Formatter) -> super::fmt::Result {
-            match &*self {
+            match self {
                 SearchOption::Class => write!(f, "--class"),
                 SearchOption::ClassName => write!(f, "--classname"),
                 SearchOption::MaxDepth(x) => write!(f, "--maxdepth {}", x),
                 SearchOption::Name => write!(f, "--name"),
+                SearchOption::Role => write!(f, "--role"),
+                SearchOption::Title => write!(f, "--title"),
                 SearchOption::OnlyVisible => write!(f, "--onlyvisible"),
                 SearchOption::Pid(x) => write!(f, "--pid {}", x),
                 SearchOption::Screen(x) => write!(f, "--screen {}", x),
@@ -289,20 +347,20 @@ pub mod options {
         }
     }
 
-    #[derive(Debug)]
+    #[derive(Debug, Clone)]
     pub enum GetWindowGeometryOption {
         Shell,
     }
 
     impl super::fmt::Display for GetWindowGeometryOption {
         fn fmt(&self, f: &mut super::fmt::Formatter) -> super::fmt::Result {
-            match &*self {
+            match self {
                 GetWindowGeometryOption::Shell => write!(f, "--shell"),
             }
         }
     }
 
-    #[derive(Debug)]
+    #[derive(Debug, Clone)]
     pub enum WindowSizeOption {
         UseHints,
         Sync,
@@ -310,14 +368,14 @@ pub mod options {
 
     impl super::fmt::Display for WindowSizeOption {
         fn fmt(&self, f: &mut super::fmt::Formatter) -> super::fmt::Result {
-            match &*self {
+            match self {
                 WindowSizeOption::Sync => write!(f, "--sync"),
                 WindowSizeOption::UseHints => write!(f, "--usehints"),
             }
         }
     }
 
-    #[derive(Debug)]
+    #[derive(Debug, Clone)]
     pub enum WindowMoveOption {
         Sync,
         Relative,
@@ -325,27 +383,61 @@ pub mod options {
 
     impl super::fmt::Display for WindowMoveOption {
         fn fmt(&self, f: &mut super::fmt::Formatter) -> super::fmt::Result {
-            match &*self {
+            match self {
                 WindowMoveOption::Sync => write!(f, "--sync"),
                 WindowMoveOption::Relative => write!(f, "--relative"),
             }
         }
     }
 
-    #[derive(Debug)]
+    #[derive(Debug, Clone)]
     pub enum SyncOption {
         Sync,
     }
 
     impl super::fmt::Display for SyncOption {
         fn fmt(&self, f: &mut super::fmt::Formatter) -> super::fmt::Result {
-            match &*self {
+            match self {
                 SyncOption::Sync => write!(f, "--sync"),
             }
         }
     }
 
-    #[derive(Debug)]
+    #[derive(Debug, Clone)]
+    pub enum ExecOption {
+        Sync,
+        Args(usize),
+        Terminator(String),
+    }
+
+    impl super::fmt::Display for ExecOption {
+        fn fmt(&self, f: &mut super::fmt::Formatter) -> super::fmt::Result {
+            match self {
+                ExecOption::Sync => write!(f, "--sync"),
+                ExecOption::Args(x) => write!(f, "--args {}", x),
+                ExecOption::Terminator(x) => write!(f, "--terminator {}", x),
+            }
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    pub enum WindowStateOption {
+        Add(String),
+        Remove(String),
+        Toggle(String),
+    }
+
+    impl super::fmt::Display for WindowStateOption {
+        fn fmt(&self, f: &mut super::fmt::Formatter) -> super::fmt::Result {
+            match self {
+                WindowStateOption::Add(x) => write!(f, "--add {}", x),
+                WindowStateOption::Remove(x) => write!(f, "--remove {}", x),
+                WindowStateOption::Toggle(x) => write!(f, "--toggle {}", x),
+            }
+        }
+    }
+
+    #[derive(Debug, Clone)]
     pub enum SetWindowOption {
         Name(String),
         IconName(String),
@@ -358,7 +450,7 @@ pub mod options {
 
     impl super::fmt::Display for SetWindowOption {
         fn fmt(&self, f: &mut super::fmt::Formatter) -> super::fmt::Result {
-            match &*self {
+            match self {
                 SetWindowOption::Name(x) => write!(f, "--name {}", x),
                 SetWindowOption::IconName(x) => write!(f, "--icon-name {}", x),
                 SetWindowOption::Role(x) => write!(f, "--role {}", x),
@@ -370,16 +462,116 @@ pub mod options {
         }
     }
 
-    #[derive(Debug)]
+    #[derive(Debug, Clone)]
     pub enum SetDesktopOption {
         Relative,
     }
 
     impl super::fmt::Display for SetDesktopOption {
         fn fmt(&self, f: &mut super::fmt::Formatter) -> super::fmt::Result {
-            match &*self {
+            match self {
                 SetDesktopOption::Relative => write!(f, "--relative"),
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::options::{ExecOption, KeyDownUpOption, KeyOption, TypeOption, WindowStateOption};
+    use super::{sub_commands, Command};
+    use crate::optionvec::OptionVec;
+    use std::fmt::Display;
+
+    /// Check every subset of `options`, in order, against the expected rendering.
+    fn assert_all_combinations<T: Display + Clone>(options: &[(T, &str)]) {
+        for mask in 0..(1u32 << options.len()) {
+            let subset: Vec<&(T, &str)> = options
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| mask & (1 << i) != 0)
+                .map(|(_, o)| o)
+                .collect();
+
+            let rendered = OptionVec(subset.iter().map(|(o, _)| o.clone()).collect()).to_string();
+            let expected: Vec<&str> = subset.iter().map(|(_, e)| *e).collect();
+            assert_eq!(rendered, expected.join(" "));
+        }
+    }
+
+    #[test]
+    fn key_options_render_in_every_combination() {
+        assert_all_combinations(&[
+            (KeyOption::Window("%1".to_owned()), "--window %1"),
+            (KeyOption::ClearModifiers, "--clearmodifiers"),
+            (KeyOption::Delay(50), "--delay 50"),
+            (KeyOption::Repeat(3), "--repeat 3"),
+            (KeyOption::RepeatDelay(100), "--repeat-delay 100"),
+        ]);
+    }
+
+    #[test]
+    fn key_down_up_options_render_in_every_combination() {
+        assert_all_combinations(&[
+            (
+                KeyDownUpOption::Window("62914567".to_owned()),
+                "--window 62914567",
+            ),
+            (KeyDownUpOption::ClearModifiers, "--clearmodifiers"),
+            (KeyDownUpOption::Delay(0), "--delay 0"),
+        ]);
+    }
+
+    #[test]
+    fn type_options_render_in_every_combination() {
+        assert_all_combinations(&[
+            (TypeOption::Window("%@".to_owned()), "--window %@"),
+            (TypeOption::ClearModifiers, "--clearmodifiers"),
+            (TypeOption::Delay(12), "--delay 12"),
+        ]);
+    }
+
+    #[test]
+    fn exec_options_render_in_every_combination() {
+        assert_all_combinations(&[
+            (ExecOption::Sync, "--sync"),
+            (ExecOption::Args(3), "--args 3"),
+            (ExecOption::Terminator("END".to_owned()), "--terminator END"),
+        ]);
+    }
+
+    #[test]
+    fn window_state_commands_render_their_options() {
+        let state = Command::Window(sub_commands::Window::WindowState(OptionVec(vec![
+            WindowStateOption::Add("ABOVE".to_owned()),
+            WindowStateOption::Remove("BELOW".to_owned()),
+            WindowStateOption::Toggle("STICKY".to_owned()),
+        ])));
+        assert_eq!(
+            state.to_string(),
+            "windowstate --add ABOVE --remove BELOW --toggle STICKY"
+        );
+    }
+
+    #[test]
+    fn keyboard_commands_render_their_options() {
+        let key = Command::Keyboard(sub_commands::Keyboard::Key(OptionVec(vec![
+            KeyOption::Repeat(2),
+            KeyOption::RepeatDelay(10),
+        ])));
+        assert_eq!(key.to_string(), "key --repeat 2 --repeat-delay 10");
+
+        let down = Command::Keyboard(sub_commands::Keyboard::KeyDown(OptionVec(vec![
+            KeyDownUpOption::Delay(5),
+        ])));
+        assert_eq!(down.to_string(), "keydown --delay 5");
+
+        let up = Command::Keyboard(sub_commands::Keyboard::KeyUp(OptionVec::new()));
+        assert_eq!(up.to_string(), "keyup ");
+
+        let type_ = Command::Keyboard(sub_commands::Keyboard::Type(OptionVec(vec![
+            TypeOption::ClearModifiers,
+        ])));
+        assert_eq!(type_.to_string(), "type --clearmodifiers");
+    }
+}
@@ -0,0 +1,188 @@
+//! Strategies to deliver keyboard and mouse input to a window that is not focused.
+//!
+//! Passing a window to xdotool (e.g. `KeyOption::Window`) makes it use XSendEvent.
+//! Many toolkits like GTK, Firefox or Electron silently ignore such synthetic events.
+//! The only reliable alternative is to give the window focus and send the input through XTEST.
+
+use crate::command::options::SyncOption;
+use crate::error::Result;
+use crate::optionvec::OptionVec;
+use crate::window::{self, WindowId};
+use crate::{desktop, option_vec, stdout};
+
+/// How input is delivered to a window.
+///
+/// # Examples
+///
+/// Use XSendEvent for xterm, started with `-xrm 'XTerm*allowSendEvents: true'`, and activate every other window:
+///
+/// ```
+/// # use xdotool::delivery::{Delivery, DeliveryRules};
+/// let mut rules = DeliveryRules::default();
+/// rules.rules.push(("xterm".to_owned(), Delivery::SendEvent));
+/// let delivery = Delivery::Auto(rules);
+/// assert_eq!(delivery.for_class("XTerm"), Delivery::SendEvent);
+/// assert_eq!(delivery.for_class("firefox"), Delivery::ActivateThenXTest);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Delivery {
+    /// Send synthetic events to the window with XSendEvent. Doesn't change the focus, but is ignored by many applications.
+    SendEvent,
+    /// Activate the window (switching desktops if necessary), then send the input through XTEST.
+    ActivateThenXTest,
+    /// Focus the window, then send the input through XTEST. Ignored by some window managers.
+    FocusThenXTest,
+    /// Choose based on the class of the window.
+    Auto(DeliveryRules),
+}
+
+impl Default for Delivery {
+    fn default() -> Delivery {
+        Delivery::Auto(DeliveryRules::default())
+    }
+}
+
+/// The rule table for `Delivery::Auto`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeliveryRules {
+    /// Window classes, compared case-insensitively, and how to deliver input to them.
+    pub rules: Vec<(String, Delivery)>,
+    /// Used for windows without a matching rule, and if the class of the window can't be read.
+    pub fallback: Box<Delivery>,
+}
+
+impl Default for DeliveryRules {
+    /// Activate every window. XSendEvent is ignored by too many applications, e.g. xterm, Qt and LibreOffice,
+    /// so it has to be enabled per class with a rule.
+    fn default() -> DeliveryRules {
+        DeliveryRules {
+            rules: Vec::new(),
+            fallback: Box::new(Delivery::ActivateThenXTest),
+        }
+    }
+}
+
+impl Delivery {
+    /// Resolve `Delivery::Auto` to the delivery for the given window.
+    ///
+    /// If the class can't be read, e.g. because xdotool is older than 3.20210804 and has no `getwindowclassname`,
+    /// the fallback is used.
+    pub fn resolve(&self, window: &str) -> Delivery {
+        match self {
+            Delivery::Auto(_) => match stdout(window::get_window_class_name(window)) {
+                Ok(class) => self.for_class(class.trim()),
+                Err(_) => self.without_class(),
+            },
+            delivery => delivery.clone(),
+        }
+    }
+
+    /// Resolve `Delivery::Auto`, including nested rule tables, for a window of the given class.
+    pub fn for_class(&self, class: &str) -> Delivery {
+        match self {
+            Delivery::Auto(table) => table
+                .rules
+                .iter()
+                .find(|(c, _)| c.eq_ignore_ascii_case(class))
+                .map_or(&*table.fallback, |(_, delivery)| delivery)
+                .for_class(class),
+            delivery => delivery.clone(),
+        }
+    }
+
+    /// Resolve `Delivery::Auto`, including nested rule tables, to the fallback.
+    fn without_class(&self) -> Delivery {
+        match self {
+            Delivery::Auto(table) => table.fallback.without_class(),
+            delivery => delivery.clone(),
+        }
+    }
+}
+
+/// Prepare `window` for input according to `delivery` and call `send`.
+///
+/// `send` receives `true` if it has to address the window with XSendEvent.
+/// If `restore` is set and the focus was changed, the previously active window is activated again afterwards.
+/// The result of `send` is returned even if restoring the focus fails, since the input was delivered anyway.
+pub(crate) fn deliver<T, F>(window: &str, delivery: &Delivery, restore: bool, send: F) -> Result<T>
+where
+    F: FnOnce(bool) -> Result<T>,
+{
+    let delivery = delivery.resolve(window);
+    if delivery == Delivery::SendEvent {
+        return send(true);
+    }
+
+    let previous: Option<WindowId> = if restore {
+        stdout(desktop::get_active_window())
+            .ok()
+            .and_then(|s| s.parse().ok())
+    } else {
+        None
+    };
+
+    let sync: OptionVec<SyncOption> = option_vec![SyncOption::Sync];
+    match delivery {
+        Delivery::FocusThenXTest => stdout(window::focus_window(window, sync))?,
+        _ => stdout(desktop::activate_window(window, sync))?,
+    };
+
+    let result = send(false);
+
+    if let Some(previous) = previous {
+        if previous.to_string() != window {
+            let _ = desktop::activate_window(&previous.to_string(), option_vec![SyncOption::Sync]);
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Delivery, DeliveryRules};
+
+    #[test]
+    fn every_class_is_activated_by_default() {
+        let delivery = Delivery::default();
+        for class in &["Firefox", "XTerm", "libreoffice-writer", "qterminal"] {
+            assert_eq!(delivery.for_class(class), Delivery::ActivateThenXTest);
+        }
+        assert_eq!(delivery.without_class(), Delivery::ActivateThenXTest);
+    }
+
+    #[test]
+    fn classes_match_case_insensitively() {
+        let mut rules = DeliveryRules::default();
+        rules.rules.push(("xterm".to_owned(), Delivery::SendEvent));
+        let delivery = Delivery::Auto(rules);
+        assert_eq!(delivery.for_class("XTerm"), Delivery::SendEvent);
+        assert_eq!(delivery.for_class("XTERM"), Delivery::SendEvent);
+        assert_eq!(delivery.for_class("Firefox"), Delivery::ActivateThenXTest);
+    }
+
+    #[test]
+    fn nested_rules_are_resolved() {
+        let terminals = DeliveryRules {
+            rules: vec![("kitty".to_owned(), Delivery::FocusThenXTest)],
+            fallback: Box::new(Delivery::SendEvent),
+        };
+        let delivery = Delivery::Auto(DeliveryRules {
+            rules: vec![("code".to_owned(), Delivery::ActivateThenXTest)],
+            fallback: Box::new(Delivery::Auto(terminals)),
+        });
+
+        assert_eq!(delivery.for_class("Code"), Delivery::ActivateThenXTest);
+        assert_eq!(delivery.for_class("kitty"), Delivery::FocusThenXTest);
+        assert_eq!(delivery.for_class("xterm"), Delivery::SendEvent);
+        assert_eq!(delivery.without_class(), Delivery::SendEvent);
+    }
+
+    #[test]
+    fn explicit_delivery_ignores_the_class() {
+        assert_eq!(
+            Delivery::FocusThenXTest.for_class("firefox"),
+            Delivery::FocusThenXTest
+        );
+    }
+}
@@ -0,0 +1,88 @@
+//! The error type returned by functions that produce typed results.
+
+use std::fmt;
+use std::io;
+use std::time::Duration;
+
+use crate::mouse::Location;
+
+/// Everything that can go wrong when interpreting the result of a command.
+#[derive(Debug)]
+pub enum Error {
+    /// The command exited with a non-zero status.
+    /// `status` is `None` if the process was terminated by a signal.
+    Failed { status: Option<i32>, stderr: String },
+    /// The output of the command could not be parsed.
+    Parse(String),
+    /// The target window lost focus while typing, so typing was stopped.
+    FocusLost { typed_so_far: usize },
+    /// Typing stopped because of `error` after `typed_so_far` characters were sent.
+    Interrupted {
+        typed_so_far: usize,
+        error: Box<Error>,
+    },
+    /// Reading the input or spawning a process failed.
+    Io(io::Error),
+    /// A search query is invalid, e.g. because of a malformed regular expression.
+    InvalidQuery(String),
+    /// The mouse pointer moved while a multi-click was sent, so the clicks may not have been recognized as one.
+    PointerMoved { from: Location, to: Location },
+    /// A wait condition was not met in time. `last` describes the last observed state.
+    Timeout { waited: Duration, last: String },
+}
+
+/// A `Result` with [`Error`](enum.Error.html) as error type.
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Failed { status, stderr } => match status {
+                Some(code) => write!(f, "command exited with status {}: {}", code, stderr.trim()),
+                None => write!(f, "command was terminated by a signal: {}", stderr.trim()),
+            },
+            Error::Parse(x) => write!(f, "failed to parse command output: {}", x),
+            Error::FocusLost { typed_so_far } => write!(
+                f,
+                "window lost focus after {} characters were typed",
+                typed_so_far
+            ),
+            Error::Interrupted {
+                typed_so_far,
+                error,
+            } => write!(
+                f,
+                "typing stopped after {} characters: {}",
+                typed_so_far, error
+            ),
+            Error::Io(e) => write!(f, "{}", e),
+            Error::InvalidQuery(x) => write!(f, "invalid search query: {}", x),
+            Error::PointerMoved { from, to } => write!(
+                f,
+                "pointer moved from {},{} to {},{} while clicking",
+                from.x, from.y, to.x, to.y
+            ),
+            Error::Timeout { waited, last } => write!(
+                f,
+                "timed out after {:?}, last observed state: {}",
+                waited, last
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::Interrupted { error, .. } => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Error {
+        Error::Io(e)
+    }
+}
@@ -1,11 +1,24 @@
 //! Convenience functions for the keyboard functionality in xdotool.
 
-use std::process::Output;
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::process::{self, Output, Stdio};
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
 
-use crate::command::options::KeyboardOption;
+use crate::chain::Chain;
+use crate::command::options::{KeyDownUpOption, KeyOption, SyncOption, TypeOption};
 use crate::command::{sub_commands, Command};
+use crate::delivery::{deliver, Delivery};
+use crate::error::{Error, Result};
+use crate::keysym;
 use crate::optionvec::OptionVec;
-use crate::run;
+use crate::window::{self, WindowId};
+use crate::{desktop, option_vec, quote, run, stdout};
 
 /// Type a given keystroke.
 /// Generally, any valid X Keysym string will work. Multiple keys are separated by '+'.
@@ -14,9 +27,11 @@ use crate::run;
 /// 
 /// # Options 
 /// 
-/// - `KeyboardOption::Window(String)` Send keystrokes to a specific window id.
-/// - `KeyboardOption::ClearModifiers` Clear modifiers before sending keystrokes.
-/// - `KeyboardOption::Delay(u32)` Delay between keystrokes. Default is 12ms.
+/// - `KeyOption::Window(String)` Send keystrokes to a specific window id.
+/// - `KeyOption::ClearModifiers` Clear modifiers before sending keystrokes.
+/// - `KeyOption::Delay(u32)` Delay between keystrokes. Default is 12ms.
+/// - `KeyOption::Repeat(u32)` Repeat the key sequence N times.
+/// - `KeyOption::RepeatDelay(u32)` Delay between repetitions in milliseconds.
 /// 
 /// # Examples
 /// 
@@ -26,30 +41,1319 @@ use crate::run;
 /// # use xdotool::command::options;
 /// # use xdotool::{keyboard, option_vec, OptionVec};
 /// keyboard::send_key("ctrl+l BackSpace", option_vec![
-///     options::KeyboardOption::Delay(200)
+///     options::KeyOption::Delay(200)
 /// ]);
 /// ```
-pub fn send_key(keys: &str, options: OptionVec<KeyboardOption>) -> Output {
+///
+/// Press Down three times with 100ms between the presses:
+///
+/// ```no_run
+/// # use xdotool::command::options;
+/// # use xdotool::{keyboard, option_vec, OptionVec};
+/// keyboard::send_key("Down", option_vec![
+///     options::KeyOption::Repeat(3),
+///     options::KeyOption::RepeatDelay(100)
+/// ]);
+/// ```
+pub fn send_key(keys: &str, options: OptionVec<KeyOption>) -> Output {
     let c = Command::Keyboard(sub_commands::Keyboard::Key(options));
     run(c, keys)
 }
 
 /// Same as [`send_key`](fn.send_key.html), except only keydown (press) events are sent.
-pub fn send_key_down(keys: &str, options: OptionVec<KeyboardOption>) -> Output {
+///
+/// # Options
+///
+/// - `KeyDownUpOption::Window(String)` Send keystrokes to a specific window id.
+/// - `KeyDownUpOption::ClearModifiers` Clear modifiers before sending keystrokes.
+/// - `KeyDownUpOption::Delay(u32)` Delay between keystrokes. Default is 12ms.
+pub fn send_key_down(keys: &str, options: OptionVec<KeyDownUpOption>) -> Output {
     let c = Command::Keyboard(sub_commands::Keyboard::KeyDown(options));
     run(c, keys)
 }
 
 /// Same as [`send_key`](fn.send_key.html), except only keyup (release) events are sent.
-pub fn send_key_up(keys: &str, options: OptionVec<KeyboardOption>) -> Output {
+/// See [`send_key_down`](fn.send_key_down.html) for information about possible options.
+pub fn send_key_up(keys: &str, options: OptionVec<KeyDownUpOption>) -> Output {
     let c = Command::Keyboard(sub_commands::Keyboard::KeyUp(options));
     run(c, keys)
 }
 
 /// Types as if you had typed it. Supports newlines tabs (ASCII newline and tab).
-/// Each keystroke is separated by a delay given by `KeyboardOption::Delay(u32)`.
-/// See [`send_key`](fn.send_key.html) for information about possible options and examples. 
-pub fn type_text(text: &str, options: OptionVec<KeyboardOption>) -> Output {
+/// Each keystroke is separated by a delay given by `TypeOption::Delay(u32)`.
+///
+/// # Options
+///
+/// - `TypeOption::Window(String)` Send keystrokes to a specific window id.
+/// - `TypeOption::ClearModifiers` Clear modifiers before sending keystrokes.
+/// - `TypeOption::Delay(u32)` Delay between keystrokes. Default is 12ms.
+///
+/// The text is passed as a single argument, so it is limited by the maximum argument length of your system.
+/// Use a [`TypeEngine`](struct.TypeEngine.html) for large inputs.
+pub fn type_text(text: &str, options: OptionVec<TypeOption>) -> Output {
     let c = Command::Keyboard(sub_commands::Keyboard::Type(options));
-    run(c, text)
+    run(c, &format!("-- {}", quote(text)))
+}
+
+/// How a [`TypeEngine`](struct.TypeEngine.html) sends characters that are likely to fail with `type`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Fallback {
+    /// Type every character with `type`.
+    None,
+    /// Send every character outside of printable ASCII as a keysym with `key`, e.g. `U20AC` for `€`.
+    Keysym,
+    /// Send every character that can't be typed directly on the given keymap as a keysym with `key`.
+    /// Use [`current_keymap`](fn.current_keymap.html) to get the keymap of the active layout.
+    Layout(Keymap),
+    /// Same as `Fallback::Layout`, but send characters with a compose or dead-key sequence if the table has one.
+    Compose(Keymap, ComposeTable),
+}
+
+impl Fallback {
+    /// The keys to send instead of typing `c`, or `None` if `c` can be typed.
+    fn keys_for(&self, c: char) -> Option<String> {
+        let keysym = format!("U{:04X}", c as u32);
+        match self {
+            Fallback::None => None,
+            Fallback::Keysym if !(' '..='~').contains(&c) => Some(keysym),
+            Fallback::Layout(keymap) if !keymap.is_direct(c) => Some(keysym),
+            Fallback::Compose(keymap, table) if !keymap.is_direct(c) => Some(
+                table
+                    .sequence(c)
+                    .map_or(keysym, |sequence| sequence.join(" ")),
+            ),
+            _ => None,
+        }
+    }
+}
+
+/// Types large texts reliably.
+///
+/// Compared to [`type_text`](fn.type_text.html) the engine
+///
+/// - splits the text into chunks of at most `chunk_size` characters, one xdotool call each,
+/// - sends newlines and tabs explicitly as `Return` and `Tab`,
+/// - optionally sends characters as keysyms, see [`Fallback`](enum.Fallback.html).
+///
+/// # Examples
+///
+/// Type a file with the default settings:
+///
+/// ```no_run
+/// # use xdotool::keyboard::TypeEngine;
+/// # use xdotool::OptionVec;
+/// let text = std::fs::read_to_string("notes.txt").unwrap();
+/// let sent = TypeEngine::default().type_text(&text, &OptionVec::new())?;
+/// println!("Typed {} characters", sent);
+/// # Ok::<(), xdotool::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct TypeEngine {
+    /// The maximum number of characters passed to a single xdotool call.
+    pub chunk_size: usize,
+    pub fallback: Fallback,
+}
+
+impl Default for TypeEngine {
+    fn default() -> TypeEngine {
+        TypeEngine {
+            chunk_size: 1000,
+            fallback: Fallback::Keysym,
+        }
+    }
+}
+
+impl TypeEngine {
+    /// Type `text` and return the number of characters sent.
+    ///
+    /// Stops at the first failing xdotool call with `Error::Interrupted`, which holds the number of characters sent before.
+    /// See [`type_text`](fn.type_text.html) for information about possible options.
+    pub fn type_text(&self, text: &str, options: &OptionVec<TypeOption>) -> Result<usize> {
+        let key_options = OptionVec(options.0.iter().map(key_option).collect());
+        let mut sent = 0;
+
+        for segment in self.segments(text) {
+            let output = match &segment {
+                Segment::Text(chunk) => {
+                    let c = Command::Keyboard(sub_commands::Keyboard::Type(options.clone()));
+                    run(c, &format!("-- {}", quote(chunk)))
+                }
+                Segment::Key(keysym) => send_key(keysym, key_options.clone()),
+            };
+
+            stdout(output).map_err(|e| interrupted(e, sent))?;
+            sent += segment.len();
+        }
+
+        Ok(sent)
+    }
+
+    /// Split `text` into the xdotool calls needed to type it.
+    fn segments<'a>(&self, text: &'a str) -> Vec<Segment<'a>> {
+        let mut segments = Vec::new();
+        let mut start = None;
+        let mut count = 0;
+
+        for (i, c) in text.char_indices() {
+            let keysym = match c {
+                '\n' => Some("Return".to_owned()),
+                '\t' => Some("Tab".to_owned()),
+                c => self.fallback.keys_for(c),
+            };
+
+            if keysym.is_some() || count == self.chunk_size.max(1) {
+                if let Some(s) = start.take() {
+                    segments.push(Segment::Text(&text[s..i]));
+                    count = 0;
+                }
+            }
+
+            match keysym {
+                Some(keysym) => segments.push(Segment::Key(keysym)),
+                None => {
+                    start.get_or_insert(i);
+                    count += 1;
+                }
+            }
+        }
+
+        if let Some(s) = start {
+            segments.push(Segment::Text(&text[s..]));
+        }
+
+        segments
+    }
+}
+
+/// Attach the number of characters typed before `error` happened.
+/// If `error` comes from a nested call that typed some characters already, they are added up.
+fn interrupted(error: Error, typed: usize) -> Error {
+    match error {
+        Error::Interrupted {
+            typed_so_far,
+            error,
+        } => Error::Interrupted {
+            typed_so_far: typed + typed_so_far,
+            error,
+        },
+        error => Error::Interrupted {
+            typed_so_far: typed,
+            error: Box::new(error),
+        },
+    }
+}
+
+/// The `key` option equivalent to a `type` option.
+fn key_option(option: &TypeOption) -> KeyOption {
+    match option {
+        TypeOption::Window(x) => KeyOption::Window(x.clone()),
+        TypeOption::ClearModifiers => KeyOption::ClearModifiers,
+        TypeOption::Delay(x) => KeyOption::Delay(*x),
+    }
+}
+
+/// A part of a text that is sent with one xdotool call.
+#[derive(Debug, PartialEq)]
+enum Segment<'a> {
+    /// Typed with `type`.
+    Text(&'a str),
+    /// A single character sent with `key`, either as keysym or as sequence of keysyms.
+    Key(String),
+}
+
+impl Segment<'_> {
+    /// The number of characters of the original text.
+    fn len(&self) -> usize {
+        match self {
+            Segment::Text(s) => s.chars().count(),
+            Segment::Key(_) => 1,
+        }
+    }
+}
+
+/// Hold the given keys for `duration`, then release them.
+///
+/// The keydown, the wait and the keyup are executed by a single xdotool process,
+/// so the keys are held for exactly `duration` without the latency of spawning a second process.
+///
+/// # Examples
+///
+/// Long-press the Return key for one second:
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use xdotool::keyboard;
+/// let output = keyboard::press_for("Return", Duration::from_secs(1));
+/// ```
+pub fn press_for(keys: &str, duration: Duration) -> Output {
+    Chain::new()
+        .then(
+            Command::Keyboard(sub_commands::Keyboard::KeyDown(OptionVec::new())),
+            keys,
+        )
+        .then(
+            Command::Misc(sub_commands::Misc::Sleep),
+            &duration.as_secs_f64().to_string(),
+        )
+        .then(
+            Command::Keyboard(sub_commands::Keyboard::KeyUp(OptionVec::new())),
+            keys,
+        )
+        .run()
+}
+
+/// Options for [`type_from_reader`](fn.type_from_reader.html) and [`type_file`](fn.type_file.html).
+#[derive(Default)]
+pub struct StreamOptions<'a> {
+    /// The engine used to type each chunk. Its `chunk_size` is also the number of bytes read at once.
+    pub engine: TypeEngine,
+    /// See [`type_text`](fn.type_text.html) for information about possible options.
+    pub keyboard: OptionVec<TypeOption>,
+    /// Called after every chunk with the number of characters typed so far.
+    /// Return `false` to stop typing.
+    pub progress: Option<Box<dyn FnMut(usize) -> bool + 'a>>,
+}
+
+/// Type everything read from `reader` and return the number of characters typed.
+///
+/// The input is read and typed chunk by chunk, so it never has to fit into memory or a single argument.
+/// Whitespace is typed exactly as read. The input has to be valid UTF-8.
+/// If the progress callback of `options` returns `false`, typing stops after the current chunk.
+///
+/// # Examples
+///
+/// Type a config snippet into a console and cancel after 10000 characters:
+///
+/// ```no_run
+/// # use xdotool::keyboard::{self, StreamOptions};
+/// let snippet = std::io::Cursor::new("[core]\n\teditor = vim\n");
+/// let options = StreamOptions {
+///     progress: Some(Box::new(|typed| {
+///         println!("{} characters typed", typed);
+///         typed < 10000
+///     })),
+///     ..StreamOptions::default()
+/// };
+/// keyboard::type_from_reader(snippet, options)?;
+/// # Ok::<(), xdotool::Error>(())
+/// ```
+pub fn type_from_reader<R: Read>(mut reader: R, mut options: StreamOptions) -> Result<usize> {
+    let mut buffer = vec![0; options.engine.chunk_size.max(4)];
+    // Bytes of a character that was split between two reads
+    let mut pending = Vec::new();
+    let mut typed = 0;
+
+    loop {
+        let n = match reader.read(&mut buffer) {
+            Ok(n) => n,
+            // Retry like `Read::read_to_end` does
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        };
+        if n == 0 {
+            break;
+        }
+        pending.extend_from_slice(&buffer[..n]);
+        let chunk = take_complete_chars(&mut pending)?;
+
+        typed += options
+            .engine
+            .type_text(&chunk, &options.keyboard)
+            .map_err(|e| interrupted(e, typed))?;
+
+        if let Some(progress) = options.progress.as_mut() {
+            if !progress(typed) {
+                return Ok(typed);
+            }
+        }
+    }
+
+    if !pending.is_empty() {
+        return Err(Error::Parse(
+            "input ends with an incomplete character".to_owned(),
+        ));
+    }
+    Ok(typed)
+}
+
+/// Decode and remove the complete characters at the start of `pending`.
+/// The bytes of a character that is split between two reads are left in `pending`.
+fn take_complete_chars(pending: &mut Vec<u8>) -> Result<String> {
+    let valid = match std::str::from_utf8(pending) {
+        Ok(_) => pending.len(),
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        Err(e) => return Err(Error::Parse(e.to_string())),
+    };
+    String::from_utf8(pending.drain(..valid).collect()).map_err(|e| Error::Parse(e.to_string()))
+}
+
+/// Type the contents of a file. See [`type_from_reader`](fn.type_from_reader.html) for details.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use xdotool::keyboard::{self, StreamOptions};
+/// let typed = keyboard::type_file("/etc/hostname", StreamOptions::default())?;
+/// # Ok::<(), xdotool::Error>(())
+/// ```
+pub fn type_file<P: AsRef<Path>>(path: P, options: StreamOptions) -> Result<usize> {
+    type_from_reader(File::open(path)?, options)
+}
+
+/// Same as [`send_key`](fn.send_key.html), but delivers the keystrokes to `window` as described by `delivery`.
+///
+/// If `restore` is set, the previously active window is activated again afterwards.
+///
+/// # Examples
+///
+/// Reload a browser window in the background:
+///
+/// ```no_run
+/// # use xdotool::delivery::Delivery;
+/// # use xdotool::{keyboard, OptionVec};
+/// keyboard::send_key_to("62914567", "F5", OptionVec::new(), &Delivery::default(), true)?;
+/// # Ok::<(), xdotool::Error>(())
+/// ```
+pub fn send_key_to(
+    window: &str,
+    keys: &str,
+    mut options: OptionVec<KeyOption>,
+    delivery: &Delivery,
+    restore: bool,
+) -> Result<()> {
+    deliver(window, delivery, restore, |send_event| {
+        if send_event {
+            options.0.push(KeyOption::Window(window.to_owned()));
+        }
+        stdout(send_key(keys, options)).map(|_| ())
+    })
+}
+
+/// Same as [`TypeEngine::type_text`](struct.TypeEngine.html#method.type_text) with the default engine,
+/// but delivers the text to `window` as described by `delivery`.
+///
+/// If `restore` is set, the previously active window is activated again afterwards.
+pub fn type_text_to(
+    window: &str,
+    text: &str,
+    mut options: OptionVec<TypeOption>,
+    delivery: &Delivery,
+    restore: bool,
+) -> Result<usize> {
+    deliver(window, delivery, restore, |send_event| {
+        if send_event {
+            options.0.push(TypeOption::Window(window.to_owned()));
+        }
+        TypeEngine::default().type_text(text, &options)
+    })
+}
+
+/// The number of characters [`type_into`](fn.type_into.html) types between two focus checks.
+const FOCUS_CHECK_INTERVAL: usize = 16;
+
+/// Activate `window` and type `text` into it, but stop as soon as the window loses focus.
+///
+/// The window is activated with [`desktop::activate_window`](../desktop/fn.activate_window.html) first.
+/// The text is then typed in small chunks and [`desktop::get_active_window`](../desktop/fn.get_active_window.html) is checked before every chunk.
+/// If another window became active, e.g. because a popup appeared, `Error::FocusLost` is returned with the number of characters typed so far.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use xdotool::keyboard;
+/// # use xdotool::{Error, OptionVec};
+/// match keyboard::type_into("62914567", "hunter2", OptionVec::new()) {
+///     Err(Error::FocusLost { typed_so_far }) => eprintln!("Focus lost after {} characters", typed_so_far),
+///     result => println!("{:?}", result),
+/// }
+/// ```
+pub fn type_into(window: &str, text: &str, options: OptionVec<TypeOption>) -> Result<usize> {
+    let target: WindowId = window.parse()?;
+    stdout(desktop::activate_window(
+        window,
+        option_vec![SyncOption::Sync],
+    ))?;
+
+    let engine = TypeEngine::default();
+    let mut typed = 0;
+    let mut rest = text;
+
+    while !rest.is_empty() {
+        let active: WindowId = stdout(desktop::get_active_window())?.parse()?;
+        if active != target {
+            return Err(Error::FocusLost {
+                typed_so_far: typed,
+            });
+        }
+
+        let end = rest
+            .char_indices()
+            .nth(FOCUS_CHECK_INTERVAL)
+            .map_or(rest.len(), |(i, _)| i);
+        typed += engine
+            .type_text(&rest[..end], &options)
+            .map_err(|e| interrupted(e, typed))?;
+        rest = &rest[end..];
+    }
+
+    Ok(typed)
+}
+
+/// The program used by [`paste_text`](fn.paste_text.html) to access the clipboard.
+/// Each variant holds the name or path of the binary, e.g. to use a local build under Xvfb.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClipboardTool {
+    Xclip(String),
+    Xsel(String),
+}
+
+impl ClipboardTool {
+    fn command(&self, xclip_args: [&str; 3], xsel_args: [&str; 2]) -> process::Command {
+        match self {
+            ClipboardTool::Xclip(bin) => {
+                let mut c = process::Command::new(bin);
+                c.args(xclip_args);
+                c
+            }
+            ClipboardTool::Xsel(bin) => {
+                let mut c = process::Command::new(bin);
+                c.args(xsel_args);
+                c
+            }
+        }
+    }
+
+    /// Read the text content of the clipboard. Returns `None` if the clipboard is empty or holds no text.
+    fn read(&self) -> Result<Option<Vec<u8>>> {
+        let output = self
+            .command(
+                ["-selection", "clipboard", "-o"],
+                ["--clipboard", "--output"],
+            )
+            .stderr(Stdio::null())
+            .output()?;
+        Ok(if output.status.success() {
+            Some(output.stdout)
+        } else {
+            None
+        })
+    }
+
+    fn write(&self, content: &[u8]) -> Result<()> {
+        // The tools keep running in the background to serve the selection, so don't capture their output
+        let mut child = self
+            .command(
+                ["-selection", "clipboard", "-i"],
+                ["--clipboard", "--input"],
+            )
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(content)?;
+        }
+
+        let status = child.wait()?;
+        if !status.success() {
+            return Err(Error::Failed {
+                status: status.code(),
+                stderr: "failed to set the clipboard".to_owned(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Options for [`paste_text`](fn.paste_text.html).
+#[derive(Debug, Clone)]
+pub struct PasteOptions {
+    pub tool: ClipboardTool,
+    /// Window classes, compared case-insensitively, and the keys that paste into them.
+    pub rules: Vec<(String, String)>,
+    /// The keys used for windows without a matching rule.
+    pub keys: String,
+    /// How long to wait for the application to fetch the clipboard before the original content is restored.
+    pub settle: Duration,
+}
+
+impl Default for PasteOptions {
+    /// Use `xclip`, paste with `ctrl+v` and with `shift+Insert` into common terminals.
+    fn default() -> PasteOptions {
+        let rules = [
+            "xterm",
+            "urxvt",
+            "alacritty",
+            "kitty",
+            "konsole",
+            "gnome-terminal-server",
+        ]
+        .iter()
+        .map(|class| (class.to_string(), "shift+Insert".to_owned()))
+        .collect();
+
+        PasteOptions {
+            tool: ClipboardTool::Xclip("xclip".to_owned()),
+            rules,
+            keys: "ctrl+v".to_owned(),
+            settle: Duration::from_millis(200),
+        }
+    }
+}
+
+/// Enter `text` into `window` by pasting it from the clipboard, which is much faster than typing long texts.
+///
+/// The current clipboard content is saved first and restored after pasting.
+/// Only text content can be restored; if the clipboard held no text, it keeps `text`.
+/// The window is activated before the paste keys are sent.
+///
+/// # Examples
+///
+/// Paste a long text using `xsel` instead of `xclip`:
+///
+/// ```no_run
+/// # use xdotool::keyboard::{self, ClipboardTool, PasteOptions};
+/// let options = PasteOptions {
+///     tool: ClipboardTool::Xsel("xsel".to_owned()),
+///     ..PasteOptions::default()
+/// };
+/// keyboard::paste_text(&"Lorem ipsum ".repeat(1000), "62914567", &options)?;
+/// # Ok::<(), xdotool::Error>(())
+/// ```
+pub fn paste_text(text: &str, window: &str, options: &PasteOptions) -> Result<()> {
+    // Look up the keys first, so that a failing lookup doesn't leave the clipboard overwritten
+    let class = stdout(window::get_window_class_name(window))?;
+    let keys = options
+        .rules
+        .iter()
+        .find(|(c, _)| c.eq_ignore_ascii_case(class.trim()))
+        .map_or(&options.keys, |(_, keys)| keys);
+
+    let saved = options.tool.read()?;
+    options.tool.write(text.as_bytes())?;
+
+    let result = deliver(window, &Delivery::ActivateThenXTest, false, |_| {
+        stdout(send_key(keys, option_vec![KeyOption::ClearModifiers])).map(|_| ())
+    });
+
+    thread::sleep(options.settle);
+    if let Some(saved) = saved {
+        options.tool.write(&saved)?;
+    }
+    result
+}
+
+/// Describes the cadence of [`type_humanlike`](fn.type_humanlike.html).
+///
+/// The same `seed` always produces the same delays and typos, which makes tests reproducible.
+#[derive(Debug, Clone)]
+pub struct TypingProfile {
+    /// The mean typing speed in words per minute, where a word is five characters.
+    pub wpm: f32,
+    /// The standard deviation of the delay between keystrokes relative to the mean, e.g. `0.3` for 30%.
+    pub variance: f32,
+    /// The factor the delay after punctuation is multiplied with.
+    pub punctuation_pause: f32,
+    /// The factor the delay within common bigrams like "th" or "er" is multiplied with.
+    pub bigram_burst: f32,
+    /// The probability of typing a wrong letter and correcting it with `BackSpace`.
+    pub typo_rate: f32,
+    pub seed: u64,
+}
+
+impl Default for TypingProfile {
+    fn default() -> TypingProfile {
+        TypingProfile {
+            wpm: 60.0,
+            variance: 0.3,
+            punctuation_pause: 2.5,
+            bigram_burst: 0.6,
+            typo_rate: 0.0,
+            seed: 0,
+        }
+    }
+}
+
+/// Type `text` with a varying, human-like delay between keystrokes and return the number of characters typed.
+///
+/// Unlike `TypeOption::Delay(u32)`, the delay follows the given [`TypingProfile`](struct.TypingProfile.html):
+/// it varies randomly around the mean speed, gets longer after punctuation and shorter within common bigrams.
+/// Every keystroke is a separate xdotool call.
+///
+/// # Examples
+///
+/// Type slowly and make a typo every now and then:
+///
+/// ```no_run
+/// # use xdotool::keyboard::{self, TypingProfile};
+/// let profile = TypingProfile {
+///     wpm: 40.0,
+///     typo_rate: 0.05,
+///     seed: 42,
+///     ..TypingProfile::default()
+/// };
+/// keyboard::type_humanlike("Hello, world!", &profile)?;
+/// # Ok::<(), xdotool::Error>(())
+/// ```
+pub fn type_humanlike(text: &str, profile: &TypingProfile) -> Result<usize> {
+    let engine = TypeEngine {
+        chunk_size: 1,
+        fallback: Fallback::Keysym,
+    };
+    let mut typed = 0;
+
+    for keystroke in plan_keystrokes(text, profile) {
+        thread::sleep(keystroke.delay);
+        match keystroke.stroke {
+            Stroke::Char(c) => {
+                engine
+                    .type_text(&c.to_string(), &OptionVec::new())
+                    .map_err(|e| interrupted(e, typed))?;
+                typed += 1;
+            }
+            Stroke::Typo(c) => {
+                engine
+                    .type_text(&c.to_string(), &OptionVec::new())
+                    .map_err(|e| interrupted(e, typed))?;
+            }
+            Stroke::BackSpace => {
+                stdout(send_key("BackSpace", OptionVec::new()))
+                    .map_err(|e| interrupted(e, typed))?;
+            }
+        }
+    }
+
+    Ok(typed)
+}
+
+/// Bigrams that are typed faster than other pairs of characters.
+const COMMON_BIGRAMS: [&str; 20] = [
+    "th", "he", "in", "er", "an", "re", "on", "at", "en", "nd", "ti", "es", "or", "te", "of", "ed",
+    "is", "it", "al", "st",
+];
+
+/// A single keystroke of [`type_humanlike`](fn.type_humanlike.html) and the delay before it.
+#[derive(Debug, Clone, PartialEq)]
+struct Keystroke {
+    delay: Duration,
+    stroke: Stroke,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Stroke {
+    /// A character of the text.
+    Char(char),
+    /// A wrong character that is removed again with the next `BackSpace`.
+    Typo(char),
+    BackSpace,
+}
+
+/// Compute the keystrokes and delays for `text`.
+fn plan_keystrokes(text: &str, profile: &TypingProfile) -> Vec<Keystroke> {
+    let mut rng = Rng::new(profile.seed);
+    let mean = 60.0 / (f64::from(profile.wpm.max(1.0)) * 5.0);
+    let delay = |factor: f32, rng: &mut Rng| {
+        let jitter = 1.0 + rng.normal() * f64::from(profile.variance);
+        Duration::from_secs_f64((mean * f64::from(factor) * jitter).max(0.0))
+    };
+
+    let mut keystrokes = Vec::new();
+    let mut previous: Option<char> = None;
+
+    for c in text.chars() {
+        let factor = match previous {
+            Some(p) if p.is_ascii_punctuation() => profile.punctuation_pause,
+            Some(p) => {
+                let bigram: String = [p, c].iter().collect::<String>().to_lowercase();
+                if COMMON_BIGRAMS.contains(&bigram.as_str()) {
+                    profile.bigram_burst
+                } else {
+                    1.0
+                }
+            }
+            None => 0.0,
+        };
+
+        if c.is_ascii_alphabetic() && rng.next_f64() < f64::from(profile.typo_rate) {
+            let typo =
+                (b'a' + (c.to_ascii_lowercase() as u8 - b'a' + 1 + rng.below(25)) % 26) as char;
+            keystrokes.push(Keystroke {
+                delay: delay(factor, &mut rng),
+                stroke: Stroke::Typo(typo),
+            });
+            // Noticing the typo takes a moment
+            keystrokes.push(Keystroke {
+                delay: delay(3.0, &mut rng),
+                stroke: Stroke::BackSpace,
+            });
+            keystrokes.push(Keystroke {
+                delay: delay(1.0, &mut rng),
+                stroke: Stroke::Char(c),
+            });
+        } else {
+            keystrokes.push(Keystroke {
+                delay: delay(factor, &mut rng),
+                stroke: Stroke::Char(c),
+            });
+        }
+
+        previous = Some(c);
+    }
+
+    keystrokes
+}
+
+/// A small SplitMix64 generator, so typing cadences are reproducible without extra dependencies.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Rng {
+        Rng(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A uniformly distributed number in `[0, 1)`.
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// A uniformly distributed number in `[0, n)`.
+    fn below(&mut self, n: u8) -> u8 {
+        (self.next_u64() % u64::from(n)) as u8
+    }
+
+    /// A standard normally distributed number (Box-Muller transform).
+    fn normal(&mut self) -> f64 {
+        let u1 = 1.0 - self.next_f64();
+        let u2 = self.next_f64();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
+}
+
+/// The keyboard layout as reported by `setxkbmap -query`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Layout {
+    pub rules: String,
+    pub model: String,
+    /// A comma-separated list if more than one layout is configured, e.g. `us,de`.
+    pub layout: String,
+    pub variant: Option<String>,
+    pub options: Option<String>,
+}
+
+impl FromStr for Layout {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut layout = Layout::default();
+
+        for line in s.lines() {
+            let mut kv = line.splitn(2, ':');
+            let (key, value) = match (kv.next(), kv.next()) {
+                (Some(k), Some(v)) => (k.trim(), v.trim().to_owned()),
+                _ => continue,
+            };
+            match key {
+                "rules" => layout.rules = value,
+                "model" => layout.model = value,
+                "layout" => layout.layout = value,
+                "variant" => layout.variant = Some(value),
+                "options" => layout.options = Some(value),
+                _ => {}
+            }
+        }
+
+        if layout.layout.is_empty() {
+            return Err(Error::Parse(format!("no layout in '{}'", s.trim())));
+        }
+        Ok(layout)
+    }
+}
+
+/// Read the active keyboard layout with `setxkbmap -query`.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use xdotool::keyboard;
+/// let layout = keyboard::current_layout()?;
+/// println!("Typing on a {} layout", layout.layout);
+/// # Ok::<(), xdotool::Error>(())
+/// ```
+pub fn current_layout() -> Result<Layout> {
+    let output = process::Command::new("setxkbmap").arg("-query").output()?;
+    stdout(output)?.parse()
+}
+
+/// The keysyms that can be typed directly, i.e. without a modifier other than shift, on a keyboard mapping.
+///
+/// Characters on higher levels like AltGr (e.g. `@` on a German layout) are not reliably typed by xdotool
+/// and are better sent as keysyms, see `Fallback::Layout`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Keymap {
+    /// The characters produced by the keysyms of the mapping.
+    chars: HashSet<char>,
+}
+
+impl Keymap {
+    /// Whether `c` can be typed directly on this keymap.
+    pub fn is_direct(&self, c: char) -> bool {
+        self.chars.contains(&c)
+    }
+}
+
+impl FromStr for Keymap {
+    type Err = Error;
+
+    /// Parse the output of `xmodmap -pk`. Only the first two columns (unshifted and shifted) of every keycode are used.
+    fn from_str(s: &str) -> Result<Self> {
+        let chars = s
+            .lines()
+            .filter(|line| {
+                line.split_whitespace()
+                    .next()
+                    .is_some_and(|keycode| keycode.parse::<u8>().is_ok())
+            })
+            .flat_map(|line| {
+                line.split_whitespace()
+                    .filter(|value| value.starts_with("0x"))
+                    .take(2)
+                    .filter_map(|value| u32::from_str_radix(&value[2..], 16).ok())
+                    .filter_map(keysym::to_char)
+                    .collect::<Vec<char>>()
+            })
+            .collect();
+
+        Ok(Keymap { chars })
+    }
+}
+
+/// Read the keyboard mapping of the active layout with `xmodmap -pk`.
+pub fn current_keymap() -> Result<Keymap> {
+    let output = process::Command::new("xmodmap").arg("-pk").output()?;
+    stdout(output)?.parse()
+}
+
+/// Return the characters of `text` that can't be typed directly on the active layout.
+///
+/// Use this to warn before typing, or type with `Fallback::Layout` to send these characters as keysyms.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use xdotool::keyboard;
+/// let missing = keyboard::untypeable_chars("user@example.com")?;
+/// if !missing.is_empty() {
+///     eprintln!("Warning: {:?} can't be typed directly on this layout", missing);
+/// }
+/// # Ok::<(), xdotool::Error>(())
+/// ```
+pub fn untypeable_chars(text: &str) -> Result<Vec<char>> {
+    let keymap = current_keymap()?;
+    let mut missing: Vec<char> = text
+        .chars()
+        .filter(|c| !c.is_control() && !keymap.is_direct(*c))
+        .collect();
+
+    missing.sort_unstable();
+    missing.dedup();
+    Ok(missing)
+}
+
+/// Send a compose or dead-key sequence, e.g. `["Multi_key", "apostrophe", "e"]` for `é`.
+///
+/// Every keysym is sent as a separate keystroke. Use a [`ComposeTable`](struct.ComposeTable.html) to find the sequence for a character.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use xdotool::keyboard;
+/// let output = keyboard::compose(&["dead_circumflex", "a"]);
+/// ```
+pub fn compose(sequence: &[&str]) -> Output {
+    send_key(&sequence.join(" "), OptionVec::new())
+}
+
+/// Compose and dead-key sequences by the character they produce, parsed from a `Compose` file.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ComposeTable {
+    sequences: HashMap<char, Vec<String>>,
+}
+
+impl ComposeTable {
+    /// Load the `Compose` file of the current locale, then the user's file from `$XCOMPOSEFILE` or `~/.XCompose`.
+    /// Sequences of the user's file take precedence. `include` directives are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use xdotool::keyboard::{self, ComposeTable};
+    /// let table = ComposeTable::system()?;
+    /// if let Some(sequence) = table.sequence('é') {
+    ///     let sequence: Vec<&str> = sequence.iter().map(String::as_str).collect();
+    ///     keyboard::compose(&sequence);
+    /// }
+    /// # Ok::<(), xdotool::Error>(())
+    /// ```
+    pub fn system() -> Result<ComposeTable> {
+        let root = Path::new("/usr/share/X11/locale");
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .iter()
+            .filter_map(|var| env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .unwrap_or_else(|| "en_US.UTF-8".to_owned());
+
+        let dir = std::fs::read_to_string(root.join("compose.dir")).unwrap_or_default();
+        let file = dir
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| {
+                let mut columns = line.split_whitespace();
+                match (columns.next(), columns.next()) {
+                    (Some(file), Some(name)) if name == locale => Some(file.trim_end_matches(':')),
+                    _ => None,
+                }
+            })
+            .next()
+            .unwrap_or("en_US.UTF-8/Compose");
+
+        let mut table: ComposeTable = std::fs::read_to_string(root.join(file))?.parse()?;
+
+        let user = env::var("XCOMPOSEFILE").ok().or_else(|| {
+            env::var("HOME")
+                .ok()
+                .map(|home| format!("{}/.XCompose", home))
+        });
+        if let Some(Ok(content)) = user.map(std::fs::read_to_string) {
+            let user: ComposeTable = content.parse()?;
+            table.sequences.extend(user.sequences);
+        }
+
+        Ok(table)
+    }
+
+    /// The shortest sequence producing `c`. Sequences starting with `Multi_key` are preferred over dead keys.
+    pub fn sequence(&self, c: char) -> Option<&Vec<String>> {
+        self.sequences.get(&c)
+    }
+}
+
+impl FromStr for ComposeTable {
+    type Err = Error;
+
+    /// Parse the content of a `Compose` file, e.g. `<Multi_key> <apostrophe> <e> : "é" eacute`.
+    /// Only sequences producing a single character are kept.
+    fn from_str(s: &str) -> Result<Self> {
+        let mut sequences: HashMap<char, Vec<String>> = HashMap::new();
+
+        for line in s.lines() {
+            let line = line.trim();
+            let (events, result) = match line.find(':') {
+                Some(i) if line.starts_with('<') => (&line[..i], &line[i + 1..]),
+                _ => continue,
+            };
+
+            let keysyms: Vec<String> = events
+                .split(['<', '>'])
+                .map(str::trim)
+                .filter(|keysym| !keysym.is_empty())
+                .map(str::to_owned)
+                .collect();
+
+            let result = result.trim_start();
+            let produced = match result.strip_prefix('"').and_then(|r| r.split('"').next()) {
+                Some(p) if p.chars().count() == 1 => p.chars().next(),
+                _ => None,
+            };
+
+            if let Some(c) = produced {
+                let better = |current: &Vec<String>| {
+                    let rank = |seq: &Vec<String>| (seq[0] != "Multi_key", seq.len());
+                    rank(&keysyms) < rank(current)
+                };
+                if sequences.get(&c).is_none_or(better) {
+                    sequences.insert(c, keysyms);
+                }
+            }
+        }
+
+        Ok(ComposeTable { sequences })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        interrupted, plan_keystrokes, take_complete_chars, type_from_reader, ComposeTable,
+        Fallback, Keymap, Layout, Segment, StreamOptions, Stroke, TypeEngine, TypingProfile,
+    };
+    use crate::error::Error;
+    use std::io::{self, Read};
+
+    #[test]
+    fn characters_split_between_reads_are_carried_over() {
+        // "aé€" with the two-byte é and the three-byte € split between reads
+        let mut pending = vec![b'a', 0xc3];
+        assert_eq!(take_complete_chars(&mut pending).unwrap(), "a");
+        assert_eq!(pending, vec![0xc3]);
+
+        pending.extend_from_slice(&[0xa9, 0xe2, 0x82]);
+        assert_eq!(take_complete_chars(&mut pending).unwrap(), "é");
+        assert_eq!(pending, vec![0xe2, 0x82]);
+
+        pending.push(0xac);
+        assert_eq!(take_complete_chars(&mut pending).unwrap(), "€");
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn invalid_utf8_is_rejected() {
+        let mut pending = vec![b'a', 0xff, b'b'];
+        assert!(matches!(
+            take_complete_chars(&mut pending),
+            Err(Error::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn truncated_final_character_is_an_error() {
+        let options = StreamOptions {
+            engine: TypeEngine {
+                chunk_size: 1,
+                fallback: Fallback::None,
+            },
+            ..StreamOptions::default()
+        };
+        // Only the first byte of "é", so nothing is typed before the input ends
+        let result = type_from_reader(&[0xc3][..], options);
+        assert!(matches!(result, Err(Error::Parse(_))));
+    }
+
+    #[test]
+    fn interrupted_reads_are_retried() {
+        /// Fails with `Interrupted` before every read of the inner reader.
+        struct Flaky<R> {
+            inner: R,
+            interrupt: bool,
+        }
+
+        impl<R: Read> Read for Flaky<R> {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                self.interrupt = !self.interrupt;
+                if self.interrupt {
+                    return Err(io::ErrorKind::Interrupted.into());
+                }
+                self.inner.read(buf)
+            }
+        }
+
+        let reader = |bytes: &'static [u8]| Flaky {
+            inner: bytes,
+            interrupt: false,
+        };
+        assert_eq!(
+            type_from_reader(reader(b""), StreamOptions::default()).unwrap(),
+            0
+        );
+        // The read after the interruption still reaches the incomplete character
+        let result = type_from_reader(reader(&[0xc3]), StreamOptions::default());
+        assert!(matches!(result, Err(Error::Parse(_))));
+    }
+
+    #[test]
+    fn interrupted_adds_up_nested_counts() {
+        let failed = Error::Failed {
+            status: Some(1),
+            stderr: String::new(),
+        };
+        let nested = interrupted(failed, 3);
+        match interrupted(nested, 10) {
+            Error::Interrupted {
+                typed_so_far,
+                error,
+            } => {
+                assert_eq!(typed_so_far, 13);
+                assert!(matches!(*error, Error::Failed { .. }));
+            }
+            other => panic!("unexpected error {:?}", other),
+        }
+    }
+
+    #[test]
+    fn segments_map_control_characters_to_keys() {
+        let engine = TypeEngine::default();
+        assert_eq!(
+            engine.segments("-a\tb\n"),
+            vec![
+                Segment::Text("-a"),
+                Segment::Key("Tab".to_owned()),
+                Segment::Text("b"),
+                Segment::Key("Return".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn segments_are_chunked() {
+        let engine = TypeEngine {
+            chunk_size: 2,
+            fallback: Fallback::None,
+        };
+        assert_eq!(
+            engine.segments("äbcde"),
+            vec![Segment::Text("äb"), Segment::Text("cd"), Segment::Text("e")]
+        );
+    }
+
+    #[test]
+    fn segments_fall_back_to_keysyms() {
+        let engine = TypeEngine::default();
+        assert_eq!(
+            engine.segments("5€"),
+            vec![Segment::Text("5"), Segment::Key("U20AC".to_owned())]
+        );
+    }
+
+    #[test]
+    fn humanlike_plan_is_reproducible_by_seed() {
+        let profile = TypingProfile {
+            typo_rate: 0.3,
+            seed: 7,
+            ..TypingProfile::default()
+        };
+        let plan = plan_keystrokes("The quick brown fox.", &profile);
+        assert_eq!(plan, plan_keystrokes("The quick brown fox.", &profile));
+
+        let other = TypingProfile { seed: 8, ..profile };
+        assert_ne!(plan, plan_keystrokes("The quick brown fox.", &other));
+    }
+
+    #[test]
+    fn humanlike_plan_corrects_typos() {
+        let profile = TypingProfile {
+            typo_rate: 1.0,
+            ..TypingProfile::default()
+        };
+        let strokes: Vec<Stroke> = plan_keystrokes("ab", &profile)
+            .into_iter()
+            .map(|k| k.stroke)
+            .collect();
+
+        assert!(matches!(strokes[0], Stroke::Typo(c) if c != 'a'));
+        assert_eq!(strokes[1], Stroke::BackSpace);
+        assert_eq!(strokes[2], Stroke::Char('a'));
+        assert_eq!(strokes[5], Stroke::Char('b'));
+    }
+
+    #[test]
+    fn layout_parses_setxkbmap_output() {
+        let layout: Layout =
+            "rules:      evdev\nmodel:      pc105\nlayout:     de\nvariant:    nodeadkeys\n"
+                .parse()
+                .unwrap();
+        assert_eq!(layout.layout, "de");
+        assert_eq!(layout.variant.as_deref(), Some("nodeadkeys"));
+        assert_eq!(layout.options, None);
+    }
+
+    #[test]
+    fn keymap_maps_legacy_keysyms_to_characters() {
+        let xmodmap = concat!(
+            // Polish: l and lstroke
+            "     46    \t0x01b3 (lstroke)\t0x01a3 (Lstroke)\t0x006c (l)\t0x004c (L)\n",
+            // Russian: Cyrillic_a on the key of f
+            "     41    \t0x06c1 (Cyrillic_a)\t0x06e1 (Cyrillic_A)\n",
+            // Latvian: rcedilla
+            "     27    \t0x03b3 (rcedilla)\t0x03a3 (Rcedilla)\n",
+            // A Unicode keysym
+            "     51    \t0x10020ac (EuroSign)\t0x0000 (NoSymbol)\n",
+        );
+        let keymap: Keymap = xmodmap.parse().unwrap();
+
+        assert!(keymap.is_direct('а'));
+        assert!(keymap.is_direct('А'));
+        assert!(keymap.is_direct('ŗ'));
+        assert!(keymap.is_direct('€'));
+        assert!(keymap.is_direct('ł'));
+        assert!(keymap.is_direct('Ł'));
+        assert!(!keymap.is_direct('l'));
+        // U+03B3 is not the same as the keysym 0x3b3 (rcedilla)
+        assert!(!keymap.is_direct('γ'));
+    }
+
+    #[test]
+    fn keymap_only_uses_unshifted_and_shifted_keysyms() {
+        let xmodmap = concat!(
+            "There are 7 KeySyms per KeyCode; KeyCodes range from 8 to 255.\n",
+            "\n",
+            "    KeyCode\tKeysym (Keysym)\t...\n",
+            "    Value  \tValue   (Name) \t...\n",
+            "\n",
+            "     24    \t0x0071 (q)\t0x0051 (Q)\t0x0071 (q)\t0x0051 (Q)\t0x0040 (at)\n",
+            "     26    \t0x0065 (e)\t0x0045 (E)\t0x20ac (EuroSign)\n",
+        );
+        let keymap: Keymap = xmodmap.parse().unwrap();
+
+        assert!(keymap.is_direct('q'));
+        assert!(keymap.is_direct('E'));
+        assert!(!keymap.is_direct('@'));
+        assert!(!keymap.is_direct('€'));
+
+        let engine = TypeEngine {
+            fallback: Fallback::Layout(keymap),
+            ..TypeEngine::default()
+        };
+        assert_eq!(
+            engine.segments("q@"),
+            vec![Segment::Text("q"), Segment::Key("U0040".to_owned())]
+        );
+    }
+
+    #[test]
+    fn compose_table_prefers_short_multi_key_sequences() {
+        let compose = concat!(
+            "# Comment\n",
+            "include \"%L\"\n",
+            "<dead_acute> <e>\t\t\t: \"é\"\teacute # LATIN SMALL LETTER E WITH ACUTE\n",
+            "<Multi_key> <acute> <e>\t\t\t: \"é\"\teacute\n",
+            "<Multi_key> <apostrophe> <e>\t\t: \"é\"\teacute\n",
+            "<dead_circumflex> <a>\t\t: \"â\"\tacircumflex\n",
+            "<Multi_key> <L> <T>\t\t: \"LT\"\n",
+        );
+        let table: ComposeTable = compose.parse().unwrap();
+
+        assert_eq!(
+            table.sequence('é'),
+            Some(&vec![
+                "Multi_key".to_owned(),
+                "acute".to_owned(),
+                "e".to_owned()
+            ])
+        );
+        assert_eq!(
+            table.sequence('â'),
+            Some(&vec!["dead_circumflex".to_owned(), "a".to_owned()])
+        );
+        assert_eq!(table.sequence('L'), None);
+
+        let engine = TypeEngine {
+            fallback: Fallback::Compose(Keymap::default(), table),
+            chunk_size: 10,
+        };
+        assert_eq!(
+            engine.segments("éß"),
+            vec![
+                Segment::Key("Multi_key acute e".to_owned()),
+                Segment::Key("U00DF".to_owned())
+            ]
+        );
+    }
 }
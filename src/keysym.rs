@@ -0,0 +1,783 @@
+//! The characters produced by keysyms.
+//!
+//! Latin-1 keysyms equal their code point and Unicode keysyms are `0x01000000 + code point`.
+//! All other (legacy) keysyms, e.g. `lstroke` (0x1b3) or `Cyrillic_a` (0x6c1), are looked up in a table
+//! generated from the `U+` annotations of `X11/keysymdef.h`.
+
+/// The character of `keysym`, if it produces one.
+pub(crate) fn to_char(keysym: u32) -> Option<char> {
+    match keysym {
+        0x20..=0x7e | 0xa0..=0xff => std::char::from_u32(keysym),
+        0x0100_0100..=0x0110_ffff => std::char::from_u32(keysym - 0x0100_0000),
+        _ => LEGACY
+            .binary_search_by_key(&keysym, |(k, _)| *k)
+            .ok()
+            .map(|i| LEGACY[i].1),
+    }
+}
+
+/// Legacy keysyms and their characters, sorted by keysym.
+const LEGACY: [(u32, char); 762] = [
+    (0x1a1, '\u{104}'),
+    (0x1a2, '\u{2d8}'),
+    (0x1a3, '\u{141}'),
+    (0x1a5, '\u{13d}'),
+    (0x1a6, '\u{15a}'),
+    (0x1a9, '\u{160}'),
+    (0x1aa, '\u{15e}'),
+    (0x1ab, '\u{164}'),
+    (0x1ac, '\u{179}'),
+    (0x1ae, '\u{17d}'),
+    (0x1af, '\u{17b}'),
+    (0x1b1, '\u{105}'),
+    (0x1b2, '\u{2db}'),
+    (0x1b3, '\u{142}'),
+    (0x1b5, '\u{13e}'),
+    (0x1b6, '\u{15b}'),
+    (0x1b7, '\u{2c7}'),
+    (0x1b9, '\u{161}'),
+    (0x1ba, '\u{15f}'),
+    (0x1bb, '\u{165}'),
+    (0x1bc, '\u{17a}'),
+    (0x1bd, '\u{2dd}'),
+    (0x1be, '\u{17e}'),
+    (0x1bf, '\u{17c}'),
+    (0x1c0, '\u{154}'),
+    (0x1c3, '\u{102}'),
+    (0x1c5, '\u{139}'),
+    (0x1c6, '\u{106}'),
+    (0x1c8, '\u{10c}'),
+    (0x1ca, '\u{118}'),
+    (0x1cc, '\u{11a}'),
+    (0x1cf, '\u{10e}'),
+    (0x1d0, '\u{110}'),
+    (0x1d1, '\u{143}'),
+    (0x1d2, '\u{147}'),
+    (0x1d5, '\u{150}'),
+    (0x1d8, '\u{158}'),
+    (0x1d9, '\u{16e}'),
+    (0x1db, '\u{170}'),
+    (0x1de, '\u{162}'),
+    (0x1e0, '\u{155}'),
+    (0x1e3, '\u{103}'),
+    (0x1e5, '\u{13a}'),
+    (0x1e6, '\u{107}'),
+    (0x1e8, '\u{10d}'),
+    (0x1ea, '\u{119}'),
+    (0x1ec, '\u{11b}'),
+    (0x1ef, '\u{10f}'),
+    (0x1f0, '\u{111}'),
+    (0x1f1, '\u{144}'),
+    (0x1f2, '\u{148}'),
+    (0x1f5, '\u{151}'),
+    (0x1f8, '\u{159}'),
+    (0x1f9, '\u{16f}'),
+    (0x1fb, '\u{171}'),
+    (0x1fe, '\u{163}'),
+    (0x1ff, '\u{2d9}'),
+    (0x2a1, '\u{126}'),
+    (0x2a6, '\u{124}'),
+    (0x2a9, '\u{130}'),
+    (0x2ab, '\u{11e}'),
+    (0x2ac, '\u{134}'),
+    (0x2b1, '\u{127}'),
+    (0x2b6, '\u{125}'),
+    (0x2b9, '\u{131}'),
+    (0x2bb, '\u{11f}'),
+    (0x2bc, '\u{135}'),
+    (0x2c5, '\u{10a}'),
+    (0x2c6, '\u{108}'),
+    (0x2d5, '\u{120}'),
+    (0x2d8, '\u{11c}'),
+    (0x2dd, '\u{16c}'),
+    (0x2de, '\u{15c}'),
+    (0x2e5, '\u{10b}'),
+    (0x2e6, '\u{109}'),
+    (0x2f5, '\u{121}'),
+    (0x2f8, '\u{11d}'),
+    (0x2fd, '\u{16d}'),
+    (0x2fe, '\u{15d}'),
+    (0x3a2, '\u{138}'),
+    (0x3a3, '\u{156}'),
+    (0x3a5, '\u{128}'),
+    (0x3a6, '\u{13b}'),
+    (0x3aa, '\u{112}'),
+    (0x3ab, '\u{122}'),
+    (0x3ac, '\u{166}'),
+    (0x3b3, '\u{157}'),
+    (0x3b5, '\u{129}'),
+    (0x3b6, '\u{13c}'),
+    (0x3ba, '\u{113}'),
+    (0x3bb, '\u{123}'),
+    (0x3bc, '\u{167}'),
+    (0x3bd, '\u{14a}'),
+    (0x3bf, '\u{14b}'),
+    (0x3c0, '\u{100}'),
+    (0x3c7, '\u{12e}'),
+    (0x3cc, '\u{116}'),
+    (0x3cf, '\u{12a}'),
+    (0x3d1, '\u{145}'),
+    (0x3d2, '\u{14c}'),
+    (0x3d3, '\u{136}'),
+    (0x3d9, '\u{172}'),
+    (0x3dd, '\u{168}'),
+    (0x3de, '\u{16a}'),
+    (0x3e0, '\u{101}'),
+    (0x3e7, '\u{12f}'),
+    (0x3ec, '\u{117}'),
+    (0x3ef, '\u{12b}'),
+    (0x3f1, '\u{146}'),
+    (0x3f2, '\u{14d}'),
+    (0x3f3, '\u{137}'),
+    (0x3f9, '\u{173}'),
+    (0x3fd, '\u{169}'),
+    (0x3fe, '\u{16b}'),
+    (0x47e, '\u{203e}'),
+    (0x4a1, '\u{3002}'),
+    (0x4a2, '\u{300c}'),
+    (0x4a3, '\u{300d}'),
+    (0x4a4, '\u{3001}'),
+    (0x4a5, '\u{30fb}'),
+    (0x4a6, '\u{30f2}'),
+    (0x4a7, '\u{30a1}'),
+    (0x4a8, '\u{30a3}'),
+    (0x4a9, '\u{30a5}'),
+    (0x4aa, '\u{30a7}'),
+    (0x4ab, '\u{30a9}'),
+    (0x4ac, '\u{30e3}'),
+    (0x4ad, '\u{30e5}'),
+    (0x4ae, '\u{30e7}'),
+    (0x4af, '\u{30c3}'),
+    (0x4b0, '\u{30fc}'),
+    (0x4b1, '\u{30a2}'),
+    (0x4b2, '\u{30a4}'),
+    (0x4b3, '\u{30a6}'),
+    (0x4b4, '\u{30a8}'),
+    (0x4b5, '\u{30aa}'),
+    (0x4b6, '\u{30ab}'),
+    (0x4b7, '\u{30ad}'),
+    (0x4b8, '\u{30af}'),
+    (0x4b9, '\u{30b1}'),
+    (0x4ba, '\u{30b3}'),
+    (0x4bb, '\u{30b5}'),
+    (0x4bc, '\u{30b7}'),
+    (0x4bd, '\u{30b9}'),
+    (0x4be, '\u{30bb}'),
+    (0x4bf, '\u{30bd}'),
+    (0x4c0, '\u{30bf}'),
+    (0x4c1, '\u{30c1}'),
+    (0x4c2, '\u{30c4}'),
+    (0x4c3, '\u{30c6}'),
+    (0x4c4, '\u{30c8}'),
+    (0x4c5, '\u{30ca}'),
+    (0x4c6, '\u{30cb}'),
+    (0x4c7, '\u{30cc}'),
+    (0x4c8, '\u{30cd}'),
+    (0x4c9, '\u{30ce}'),
+    (0x4ca, '\u{30cf}'),
+    (0x4cb, '\u{30d2}'),
+    (0x4cc, '\u{30d5}'),
+    (0x4cd, '\u{30d8}'),
+    (0x4ce, '\u{30db}'),
+    (0x4cf, '\u{30de}'),
+    (0x4d0, '\u{30df}'),
+    (0x4d1, '\u{30e0}'),
+    (0x4d2, '\u{30e1}'),
+    (0x4d3, '\u{30e2}'),
+    (0x4d4, '\u{30e4}'),
+    (0x4d5, '\u{30e6}'),
+    (0x4d6, '\u{30e8}'),
+    (0x4d7, '\u{30e9}'),
+    (0x4d8, '\u{30ea}'),
+    (0x4d9, '\u{30eb}'),
+    (0x4da, '\u{30ec}'),
+    (0x4db, '\u{30ed}'),
+    (0x4dc, '\u{30ef}'),
+    (0x4dd, '\u{30f3}'),
+    (0x4de, '\u{309b}'),
+    (0x4df, '\u{309c}'),
+    (0x5ac, '\u{60c}'),
+    (0x5bb, '\u{61b}'),
+    (0x5bf, '\u{61f}'),
+    (0x5c1, '\u{621}'),
+    (0x5c2, '\u{622}'),
+    (0x5c3, '\u{623}'),
+    (0x5c4, '\u{624}'),
+    (0x5c5, '\u{625}'),
+    (0x5c6, '\u{626}'),
+    (0x5c7, '\u{627}'),
+    (0x5c8, '\u{628}'),
+    (0x5c9, '\u{629}'),
+    (0x5ca, '\u{62a}'),
+    (0x5cb, '\u{62b}'),
+    (0x5cc, '\u{62c}'),
+    (0x5cd, '\u{62d}'),
+    (0x5ce, '\u{62e}'),
+    (0x5cf, '\u{62f}'),
+    (0x5d0, '\u{630}'),
+    (0x5d1, '\u{631}'),
+    (0x5d2, '\u{632}'),
+    (0x5d3, '\u{633}'),
+    (0x5d4, '\u{634}'),
+    (0x5d5, '\u{635}'),
+    (0x5d6, '\u{636}'),
+    (0x5d7, '\u{637}'),
+    (0x5d8, '\u{638}'),
+    (0x5d9, '\u{639}'),
+    (0x5da, '\u{63a}'),
+    (0x5e0, '\u{640}'),
+    (0x5e1, '\u{641}'),
+    (0x5e2, '\u{642}'),
+    (0x5e3, '\u{643}'),
+    (0x5e4, '\u{644}'),
+    (0x5e5, '\u{645}'),
+    (0x5e6, '\u{646}'),
+    (0x5e7, '\u{647}'),
+    (0x5e8, '\u{648}'),
+    (0x5e9, '\u{649}'),
+    (0x5ea, '\u{64a}'),
+    (0x5eb, '\u{64b}'),
+    (0x5ec, '\u{64c}'),
+    (0x5ed, '\u{64d}'),
+    (0x5ee, '\u{64e}'),
+    (0x5ef, '\u{64f}'),
+    (0x5f0, '\u{650}'),
+    (0x5f1, '\u{651}'),
+    (0x5f2, '\u{652}'),
+    (0x6a1, '\u{452}'),
+    (0x6a2, '\u{453}'),
+    (0x6a3, '\u{451}'),
+    (0x6a4, '\u{454}'),
+    (0x6a5, '\u{455}'),
+    (0x6a6, '\u{456}'),
+    (0x6a7, '\u{457}'),
+    (0x6a8, '\u{458}'),
+    (0x6a9, '\u{459}'),
+    (0x6aa, '\u{45a}'),
+    (0x6ab, '\u{45b}'),
+    (0x6ac, '\u{45c}'),
+    (0x6ad, '\u{491}'),
+    (0x6ae, '\u{45e}'),
+    (0x6af, '\u{45f}'),
+    (0x6b0, '\u{2116}'),
+    (0x6b1, '\u{402}'),
+    (0x6b2, '\u{403}'),
+    (0x6b3, '\u{401}'),
+    (0x6b4, '\u{404}'),
+    (0x6b5, '\u{405}'),
+    (0x6b6, '\u{406}'),
+    (0x6b7, '\u{407}'),
+    (0x6b8, '\u{408}'),
+    (0x6b9, '\u{409}'),
+    (0x6ba, '\u{40a}'),
+    (0x6bb, '\u{40b}'),
+    (0x6bc, '\u{40c}'),
+    (0x6bd, '\u{490}'),
+    (0x6be, '\u{40e}'),
+    (0x6bf, '\u{40f}'),
+    (0x6c0, '\u{44e}'),
+    (0x6c1, '\u{430}'),
+    (0x6c2, '\u{431}'),
+    (0x6c3, '\u{446}'),
+    (0x6c4, '\u{434}'),
+    (0x6c5, '\u{435}'),
+    (0x6c6, '\u{444}'),
+    (0x6c7, '\u{433}'),
+    (0x6c8, '\u{445}'),
+    (0x6c9, '\u{438}'),
+    (0x6ca, '\u{439}'),
+    (0x6cb, '\u{43a}'),
+    (0x6cc, '\u{43b}'),
+    (0x6cd, '\u{43c}'),
+    (0x6ce, '\u{43d}'),
+    (0x6cf, '\u{43e}'),
+    (0x6d0, '\u{43f}'),
+    (0x6d1, '\u{44f}'),
+    (0x6d2, '\u{440}'),
+    (0x6d3, '\u{441}'),
+    (0x6d4, '\u{442}'),
+    (0x6d5, '\u{443}'),
+    (0x6d6, '\u{436}'),
+    (0x6d7, '\u{432}'),
+    (0x6d8, '\u{44c}'),
+    (0x6d9, '\u{44b}'),
+    (0x6da, '\u{437}'),
+    (0x6db, '\u{448}'),
+    (0x6dc, '\u{44d}'),
+    (0x6dd, '\u{449}'),
+    (0x6de, '\u{447}'),
+    (0x6df, '\u{44a}'),
+    (0x6e0, '\u{42e}'),
+    (0x6e1, '\u{410}'),
+    (0x6e2, '\u{411}'),
+    (0x6e3, '\u{426}'),
+    (0x6e4, '\u{414}'),
+    (0x6e5, '\u{415}'),
+    (0x6e6, '\u{424}'),
+    (0x6e7, '\u{413}'),
+    (0x6e8, '\u{425}'),
+    (0x6e9, '\u{418}'),
+    (0x6ea, '\u{419}'),
+    (0x6eb, '\u{41a}'),
+    (0x6ec, '\u{41b}'),
+    (0x6ed, '\u{41c}'),
+    (0x6ee, '\u{41d}'),
+    (0x6ef, '\u{41e}'),
+    (0x6f0, '\u{41f}'),
+    (0x6f1, '\u{42f}'),
+    (0x6f2, '\u{420}'),
+    (0x6f3, '\u{421}'),
+    (0x6f4, '\u{422}'),
+    (0x6f5, '\u{423}'),
+    (0x6f6, '\u{416}'),
+    (0x6f7, '\u{412}'),
+    (0x6f8, '\u{42c}'),
+    (0x6f9, '\u{42b}'),
+    (0x6fa, '\u{417}'),
+    (0x6fb, '\u{428}'),
+    (0x6fc, '\u{42d}'),
+    (0x6fd, '\u{429}'),
+    (0x6fe, '\u{427}'),
+    (0x6ff, '\u{42a}'),
+    (0x7a1, '\u{386}'),
+    (0x7a2, '\u{388}'),
+    (0x7a3, '\u{389}'),
+    (0x7a4, '\u{38a}'),
+    (0x7a5, '\u{3aa}'),
+    (0x7a7, '\u{38c}'),
+    (0x7a8, '\u{38e}'),
+    (0x7a9, '\u{3ab}'),
+    (0x7ab, '\u{38f}'),
+    (0x7ae, '\u{385}'),
+    (0x7af, '\u{2015}'),
+    (0x7b1, '\u{3ac}'),
+    (0x7b2, '\u{3ad}'),
+    (0x7b3, '\u{3ae}'),
+    (0x7b4, '\u{3af}'),
+    (0x7b5, '\u{3ca}'),
+    (0x7b6, '\u{390}'),
+    (0x7b7, '\u{3cc}'),
+    (0x7b8, '\u{3cd}'),
+    (0x7b9, '\u{3cb}'),
+    (0x7ba, '\u{3b0}'),
+    (0x7bb, '\u{3ce}'),
+    (0x7c1, '\u{391}'),
+    (0x7c2, '\u{392}'),
+    (0x7c3, '\u{393}'),
+    (0x7c4, '\u{394}'),
+    (0x7c5, '\u{395}'),
+    (0x7c6, '\u{396}'),
+    (0x7c7, '\u{397}'),
+    (0x7c8, '\u{398}'),
+    (0x7c9, '\u{399}'),
+    (0x7ca, '\u{39a}'),
+    (0x7cb, '\u{39b}'),
+    (0x7cc, '\u{39c}'),
+    (0x7cd, '\u{39d}'),
+    (0x7ce, '\u{39e}'),
+    (0x7cf, '\u{39f}'),
+    (0x7d0, '\u{3a0}'),
+    (0x7d1, '\u{3a1}'),
+    (0x7d2, '\u{3a3}'),
+    (0x7d4, '\u{3a4}'),
+    (0x7d5, '\u{3a5}'),
+    (0x7d6, '\u{3a6}'),
+    (0x7d7, '\u{3a7}'),
+    (0x7d8, '\u{3a8}'),
+    (0x7d9, '\u{3a9}'),
+    (0x7e1, '\u{3b1}'),
+    (0x7e2, '\u{3b2}'),
+    (0x7e3, '\u{3b3}'),
+    (0x7e4, '\u{3b4}'),
+    (0x7e5, '\u{3b5}'),
+    (0x7e6, '\u{3b6}'),
+    (0x7e7, '\u{3b7}'),
+    (0x7e8, '\u{3b8}'),
+    (0x7e9, '\u{3b9}'),
+    (0x7ea, '\u{3ba}'),
+    (0x7eb, '\u{3bb}'),
+    (0x7ec, '\u{3bc}'),
+    (0x7ed, '\u{3bd}'),
+    (0x7ee, '\u{3be}'),
+    (0x7ef, '\u{3bf}'),
+    (0x7f0, '\u{3c0}'),
+    (0x7f1, '\u{3c1}'),
+    (0x7f2, '\u{3c3}'),
+    (0x7f3, '\u{3c2}'),
+    (0x7f4, '\u{3c4}'),
+    (0x7f5, '\u{3c5}'),
+    (0x7f6, '\u{3c6}'),
+    (0x7f7, '\u{3c7}'),
+    (0x7f8, '\u{3c8}'),
+    (0x7f9, '\u{3c9}'),
+    (0x8a1, '\u{23b7}'),
+    (0x8a2, '\u{250c}'),
+    (0x8a3, '\u{2500}'),
+    (0x8a4, '\u{2320}'),
+    (0x8a5, '\u{2321}'),
+    (0x8a6, '\u{2502}'),
+    (0x8a7, '\u{23a1}'),
+    (0x8a8, '\u{23a3}'),
+    (0x8a9, '\u{23a4}'),
+    (0x8aa, '\u{23a6}'),
+    (0x8ab, '\u{239b}'),
+    (0x8ac, '\u{239d}'),
+    (0x8ad, '\u{239e}'),
+    (0x8ae, '\u{23a0}'),
+    (0x8af, '\u{23a8}'),
+    (0x8b0, '\u{23ac}'),
+    (0x8bc, '\u{2264}'),
+    (0x8bd, '\u{2260}'),
+    (0x8be, '\u{2265}'),
+    (0x8bf, '\u{222b}'),
+    (0x8c0, '\u{2234}'),
+    (0x8c1, '\u{221d}'),
+    (0x8c2, '\u{221e}'),
+    (0x8c5, '\u{2207}'),
+    (0x8c8, '\u{223c}'),
+    (0x8c9, '\u{2243}'),
+    (0x8cd, '\u{21d4}'),
+    (0x8ce, '\u{21d2}'),
+    (0x8cf, '\u{2261}'),
+    (0x8d6, '\u{221a}'),
+    (0x8da, '\u{2282}'),
+    (0x8db, '\u{2283}'),
+    (0x8dc, '\u{2229}'),
+    (0x8dd, '\u{222a}'),
+    (0x8de, '\u{2227}'),
+    (0x8df, '\u{2228}'),
+    (0x8ef, '\u{2202}'),
+    (0x8f6, '\u{192}'),
+    (0x8fb, '\u{2190}'),
+    (0x8fc, '\u{2191}'),
+    (0x8fd, '\u{2192}'),
+    (0x8fe, '\u{2193}'),
+    (0x9e0, '\u{25c6}'),
+    (0x9e1, '\u{2592}'),
+    (0x9e2, '\u{2409}'),
+    (0x9e3, '\u{240c}'),
+    (0x9e4, '\u{240d}'),
+    (0x9e5, '\u{240a}'),
+    (0x9e8, '\u{2424}'),
+    (0x9e9, '\u{240b}'),
+    (0x9ea, '\u{2518}'),
+    (0x9eb, '\u{2510}'),
+    (0x9ec, '\u{250c}'),
+    (0x9ed, '\u{2514}'),
+    (0x9ee, '\u{253c}'),
+    (0x9ef, '\u{23ba}'),
+    (0x9f0, '\u{23bb}'),
+    (0x9f1, '\u{2500}'),
+    (0x9f2, '\u{23bc}'),
+    (0x9f3, '\u{23bd}'),
+    (0x9f4, '\u{251c}'),
+    (0x9f5, '\u{2524}'),
+    (0x9f6, '\u{2534}'),
+    (0x9f7, '\u{252c}'),
+    (0x9f8, '\u{2502}'),
+    (0xaa1, '\u{2003}'),
+    (0xaa2, '\u{2002}'),
+    (0xaa3, '\u{2004}'),
+    (0xaa4, '\u{2005}'),
+    (0xaa5, '\u{2007}'),
+    (0xaa6, '\u{2008}'),
+    (0xaa7, '\u{2009}'),
+    (0xaa8, '\u{200a}'),
+    (0xaa9, '\u{2014}'),
+    (0xaaa, '\u{2013}'),
+    (0xaac, '\u{2423}'),
+    (0xaae, '\u{2026}'),
+    (0xaaf, '\u{2025}'),
+    (0xab0, '\u{2153}'),
+    (0xab1, '\u{2154}'),
+    (0xab2, '\u{2155}'),
+    (0xab3, '\u{2156}'),
+    (0xab4, '\u{2157}'),
+    (0xab5, '\u{2158}'),
+    (0xab6, '\u{2159}'),
+    (0xab7, '\u{215a}'),
+    (0xab8, '\u{2105}'),
+    (0xabb, '\u{2012}'),
+    (0xabc, '\u{2329}'),
+    (0xabd, '\u{2e}'),
+    (0xabe, '\u{232a}'),
+    (0xac3, '\u{215b}'),
+    (0xac4, '\u{215c}'),
+    (0xac5, '\u{215d}'),
+    (0xac6, '\u{215e}'),
+    (0xac9, '\u{2122}'),
+    (0xaca, '\u{2613}'),
+    (0xacc, '\u{25c1}'),
+    (0xacd, '\u{25b7}'),
+    (0xace, '\u{25cb}'),
+    (0xacf, '\u{25af}'),
+    (0xad0, '\u{2018}'),
+    (0xad1, '\u{2019}'),
+    (0xad2, '\u{201c}'),
+    (0xad3, '\u{201d}'),
+    (0xad4, '\u{211e}'),
+    (0xad5, '\u{2030}'),
+    (0xad6, '\u{2032}'),
+    (0xad7, '\u{2033}'),
+    (0xad9, '\u{271d}'),
+    (0xadb, '\u{25ac}'),
+    (0xadc, '\u{25c0}'),
+    (0xadd, '\u{25b6}'),
+    (0xade, '\u{25cf}'),
+    (0xadf, '\u{25ae}'),
+    (0xae0, '\u{25e6}'),
+    (0xae1, '\u{25ab}'),
+    (0xae2, '\u{25ad}'),
+    (0xae3, '\u{25b3}'),
+    (0xae4, '\u{25bd}'),
+    (0xae5, '\u{2606}'),
+    (0xae6, '\u{2022}'),
+    (0xae7, '\u{25aa}'),
+    (0xae8, '\u{25b2}'),
+    (0xae9, '\u{25bc}'),
+    (0xaea, '\u{261c}'),
+    (0xaeb, '\u{261e}'),
+    (0xaec, '\u{2663}'),
+    (0xaed, '\u{2666}'),
+    (0xaee, '\u{2665}'),
+    (0xaf0, '\u{2720}'),
+    (0xaf1, '\u{2020}'),
+    (0xaf2, '\u{2021}'),
+    (0xaf3, '\u{2713}'),
+    (0xaf4, '\u{2717}'),
+    (0xaf5, '\u{266f}'),
+    (0xaf6, '\u{266d}'),
+    (0xaf7, '\u{2642}'),
+    (0xaf8, '\u{2640}'),
+    (0xaf9, '\u{260e}'),
+    (0xafa, '\u{2315}'),
+    (0xafb, '\u{2117}'),
+    (0xafc, '\u{2038}'),
+    (0xafd, '\u{201a}'),
+    (0xafe, '\u{201e}'),
+    (0xba3, '\u{3c}'),
+    (0xba6, '\u{3e}'),
+    (0xba8, '\u{2228}'),
+    (0xba9, '\u{2227}'),
+    (0xbc0, '\u{af}'),
+    (0xbc2, '\u{22a4}'),
+    (0xbc3, '\u{2229}'),
+    (0xbc4, '\u{230a}'),
+    (0xbc6, '\u{5f}'),
+    (0xbca, '\u{2218}'),
+    (0xbcc, '\u{2395}'),
+    (0xbce, '\u{22a5}'),
+    (0xbcf, '\u{25cb}'),
+    (0xbd3, '\u{2308}'),
+    (0xbd6, '\u{222a}'),
+    (0xbd8, '\u{2283}'),
+    (0xbda, '\u{2282}'),
+    (0xbdc, '\u{22a3}'),
+    (0xbfc, '\u{22a2}'),
+    (0xcdf, '\u{2017}'),
+    (0xce0, '\u{5d0}'),
+    (0xce1, '\u{5d1}'),
+    (0xce2, '\u{5d2}'),
+    (0xce3, '\u{5d3}'),
+    (0xce4, '\u{5d4}'),
+    (0xce5, '\u{5d5}'),
+    (0xce6, '\u{5d6}'),
+    (0xce7, '\u{5d7}'),
+    (0xce8, '\u{5d8}'),
+    (0xce9, '\u{5d9}'),
+    (0xcea, '\u{5da}'),
+    (0xceb, '\u{5db}'),
+    (0xcec, '\u{5dc}'),
+    (0xced, '\u{5dd}'),
+    (0xcee, '\u{5de}'),
+    (0xcef, '\u{5df}'),
+    (0xcf0, '\u{5e0}'),
+    (0xcf1, '\u{5e1}'),
+    (0xcf2, '\u{5e2}'),
+    (0xcf3, '\u{5e3}'),
+    (0xcf4, '\u{5e4}'),
+    (0xcf5, '\u{5e5}'),
+    (0xcf6, '\u{5e6}'),
+    (0xcf7, '\u{5e7}'),
+    (0xcf8, '\u{5e8}'),
+    (0xcf9, '\u{5e9}'),
+    (0xcfa, '\u{5ea}'),
+    (0xda1, '\u{e01}'),
+    (0xda2, '\u{e02}'),
+    (0xda3, '\u{e03}'),
+    (0xda4, '\u{e04}'),
+    (0xda5, '\u{e05}'),
+    (0xda6, '\u{e06}'),
+    (0xda7, '\u{e07}'),
+    (0xda8, '\u{e08}'),
+    (0xda9, '\u{e09}'),
+    (0xdaa, '\u{e0a}'),
+    (0xdab, '\u{e0b}'),
+    (0xdac, '\u{e0c}'),
+    (0xdad, '\u{e0d}'),
+    (0xdae, '\u{e0e}'),
+    (0xdaf, '\u{e0f}'),
+    (0xdb0, '\u{e10}'),
+    (0xdb1, '\u{e11}'),
+    (0xdb2, '\u{e12}'),
+    (0xdb3, '\u{e13}'),
+    (0xdb4, '\u{e14}'),
+    (0xdb5, '\u{e15}'),
+    (0xdb6, '\u{e16}'),
+    (0xdb7, '\u{e17}'),
+    (0xdb8, '\u{e18}'),
+    (0xdb9, '\u{e19}'),
+    (0xdba, '\u{e1a}'),
+    (0xdbb, '\u{e1b}'),
+    (0xdbc, '\u{e1c}'),
+    (0xdbd, '\u{e1d}'),
+    (0xdbe, '\u{e1e}'),
+    (0xdbf, '\u{e1f}'),
+    (0xdc0, '\u{e20}'),
+    (0xdc1, '\u{e21}'),
+    (0xdc2, '\u{e22}'),
+    (0xdc3, '\u{e23}'),
+    (0xdc4, '\u{e24}'),
+    (0xdc5, '\u{e25}'),
+    (0xdc6, '\u{e26}'),
+    (0xdc7, '\u{e27}'),
+    (0xdc8, '\u{e28}'),
+    (0xdc9, '\u{e29}'),
+    (0xdca, '\u{e2a}'),
+    (0xdcb, '\u{e2b}'),
+    (0xdcc, '\u{e2c}'),
+    (0xdcd, '\u{e2d}'),
+    (0xdce, '\u{e2e}'),
+    (0xdcf, '\u{e2f}'),
+    (0xdd0, '\u{e30}'),
+    (0xdd1, '\u{e31}'),
+    (0xdd2, '\u{e32}'),
+    (0xdd3, '\u{e33}'),
+    (0xdd4, '\u{e34}'),
+    (0xdd5, '\u{e35}'),
+    (0xdd6, '\u{e36}'),
+    (0xdd7, '\u{e37}'),
+    (0xdd8, '\u{e38}'),
+    (0xdd9, '\u{e39}'),
+    (0xdda, '\u{e3a}'),
+    (0xddf, '\u{e3f}'),
+    (0xde0, '\u{e40}'),
+    (0xde1, '\u{e41}'),
+    (0xde2, '\u{e42}'),
+    (0xde3, '\u{e43}'),
+    (0xde4, '\u{e44}'),
+    (0xde5, '\u{e45}'),
+    (0xde6, '\u{e46}'),
+    (0xde7, '\u{e47}'),
+    (0xde8, '\u{e48}'),
+    (0xde9, '\u{e49}'),
+    (0xdea, '\u{e4a}'),
+    (0xdeb, '\u{e4b}'),
+    (0xdec, '\u{e4c}'),
+    (0xded, '\u{e4d}'),
+    (0xdf0, '\u{e50}'),
+    (0xdf1, '\u{e51}'),
+    (0xdf2, '\u{e52}'),
+    (0xdf3, '\u{e53}'),
+    (0xdf4, '\u{e54}'),
+    (0xdf5, '\u{e55}'),
+    (0xdf6, '\u{e56}'),
+    (0xdf7, '\u{e57}'),
+    (0xdf8, '\u{e58}'),
+    (0xdf9, '\u{e59}'),
+    (0xea1, '\u{3131}'),
+    (0xea2, '\u{3132}'),
+    (0xea3, '\u{3133}'),
+    (0xea4, '\u{3134}'),
+    (0xea5, '\u{3135}'),
+    (0xea6, '\u{3136}'),
+    (0xea7, '\u{3137}'),
+    (0xea8, '\u{3138}'),
+    (0xea9, '\u{3139}'),
+    (0xeaa, '\u{313a}'),
+    (0xeab, '\u{313b}'),
+    (0xeac, '\u{313c}'),
+    (0xead, '\u{313d}'),
+    (0xeae, '\u{313e}'),
+    (0xeaf, '\u{313f}'),
+    (0xeb0, '\u{3140}'),
+    (0xeb1, '\u{3141}'),
+    (0xeb2, '\u{3142}'),
+    (0xeb3, '\u{3143}'),
+    (0xeb4, '\u{3144}'),
+    (0xeb5, '\u{3145}'),
+    (0xeb6, '\u{3146}'),
+    (0xeb7, '\u{3147}'),
+    (0xeb8, '\u{3148}'),
+    (0xeb9, '\u{3149}'),
+    (0xeba, '\u{314a}'),
+    (0xebb, '\u{314b}'),
+    (0xebc, '\u{314c}'),
+    (0xebd, '\u{314d}'),
+    (0xebe, '\u{314e}'),
+    (0xebf, '\u{314f}'),
+    (0xec0, '\u{3150}'),
+    (0xec1, '\u{3151}'),
+    (0xec2, '\u{3152}'),
+    (0xec3, '\u{3153}'),
+    (0xec4, '\u{3154}'),
+    (0xec5, '\u{3155}'),
+    (0xec6, '\u{3156}'),
+    (0xec7, '\u{3157}'),
+    (0xec8, '\u{3158}'),
+    (0xec9, '\u{3159}'),
+    (0xeca, '\u{315a}'),
+    (0xecb, '\u{315b}'),
+    (0xecc, '\u{315c}'),
+    (0xecd, '\u{315d}'),
+    (0xece, '\u{315e}'),
+    (0xecf, '\u{315f}'),
+    (0xed0, '\u{3160}'),
+    (0xed1, '\u{3161}'),
+    (0xed2, '\u{3162}'),
+    (0xed3, '\u{3163}'),
+    (0xed4, '\u{11a8}'),
+    (0xed5, '\u{11a9}'),
+    (0xed6, '\u{11aa}'),
+    (0xed7, '\u{11ab}'),
+    (0xed8, '\u{11ac}'),
+    (0xed9, '\u{11ad}'),
+    (0xeda, '\u{11ae}'),
+    (0xedb, '\u{11af}'),
+    (0xedc, '\u{11b0}'),
+    (0xedd, '\u{11b1}'),
+    (0xede, '\u{11b2}'),
+    (0xedf, '\u{11b3}'),
+    (0xee0, '\u{11b4}'),
+    (0xee1, '\u{11b5}'),
+    (0xee2, '\u{11b6}'),
+    (0xee3, '\u{11b7}'),
+    (0xee4, '\u{11b8}'),
+    (0xee5, '\u{11b9}'),
+    (0xee6, '\u{11ba}'),
+    (0xee7, '\u{11bb}'),
+    (0xee8, '\u{11bc}'),
+    (0xee9, '\u{11bd}'),
+    (0xeea, '\u{11be}'),
+    (0xeeb, '\u{11bf}'),
+    (0xeec, '\u{11c0}'),
+    (0xeed, '\u{11c1}'),
+    (0xeee, '\u{11c2}'),
+    (0xeef, '\u{316d}'),
+    (0xef0, '\u{3171}'),
+    (0xef1, '\u{3178}'),
+    (0xef2, '\u{317f}'),
+    (0xef3, '\u{3181}'),
+    (0xef4, '\u{3184}'),
+    (0xef5, '\u{3186}'),
+    (0xef6, '\u{318d}'),
+    (0xef7, '\u{318e}'),
+    (0xef8, '\u{11eb}'),
+    (0xef9, '\u{11f0}'),
+    (0xefa, '\u{11f9}'),
+    (0xeff, '\u{20a9}'),
+    (0x13bc, '\u{152}'),
+    (0x13bd, '\u{153}'),
+    (0x13be, '\u{178}'),
+    (0x20ac, '\u{20ac}'),
+];
@@ -0,0 +1,192 @@
+//! Launch an application and find its window.
+//!
+//! Searching for the window of a new application by name is racy and may find an older instance.
+//! [`launch`](fn.launch.html) spawns the process itself and matches windows by its pid instead.
+
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::process::{self, Child};
+use std::time::Duration;
+
+use crate::error::{Error, Result};
+use crate::search::SearchQuery;
+use crate::wait::{self, Backoff};
+use crate::window::{Window, WindowId};
+
+/// Options for [`launch`](fn.launch.html).
+#[derive(Debug, Clone, PartialEq)]
+pub struct LaunchOptions {
+    /// Additional environment variables of the process.
+    pub env: Vec<(String, String)>,
+    /// The working directory of the process. Defaults to the current one.
+    pub cwd: Option<PathBuf>,
+    /// Give up waiting for a window after this time.
+    pub timeout: Duration,
+    /// The option of the application that sets its WM_CLASS, e.g. `--class` for GTK applications, `-class` for xterm
+    /// or `--class=` for Chromium. If set, a unique class is passed and the window is matched by it.
+    pub class_option: Option<String>,
+}
+
+impl Default for LaunchOptions {
+    fn default() -> Self {
+        LaunchOptions {
+            env: Vec::new(),
+            cwd: None,
+            timeout: Duration::from_secs(10),
+            class_option: None,
+        }
+    }
+}
+
+/// Spawn `argv` and wait for its first visible window.
+///
+/// The window is matched by the pid of the process. Applications that fork are matched by the pids of all descendants.
+/// Applications that hand over to an already running instance and exit can only be matched with `class_option`.
+///
+/// `SearchOption::Sync` would block forever if no window appears, so the search is repeated until `timeout`.
+/// On timeout the process is killed, since its handle can't be returned.
+///
+/// # Examples
+///
+/// Start a terminal in the home directory and type into it:
+///
+/// ```no_run
+/// # use xdotool::launch::{self, LaunchOptions};
+/// let options = LaunchOptions {
+///     cwd: Some("/home".into()),
+///     class_option: Some("-class".to_owned()),
+///     ..LaunchOptions::default()
+/// };
+/// let (child, window) = launch::launch(&["xterm", "-fa", "Monospace"], &options)?;
+/// window.type_text("ls\n")?;
+/// # Ok::<(), xdotool::Error>(())
+/// ```
+pub fn launch(argv: &[&str], options: &LaunchOptions) -> Result<(Child, Window)> {
+    let (program, args) = argv
+        .split_first()
+        .ok_or_else(|| Error::Io(io::Error::new(io::ErrorKind::InvalidInput, "empty argv")))?;
+
+    let mut command = process::Command::new(program);
+    command.envs(options.env.iter().map(|(k, v)| (k, v)));
+    if let Some(cwd) = &options.cwd {
+        command.current_dir(cwd);
+    }
+
+    let class = options.class_option.as_ref().map(|option| {
+        let class = format!("xdotool-{}-{}", process::id(), unique_suffix());
+        if option.ends_with('=') {
+            command.arg(format!("{}{}", option, class));
+        } else {
+            command.args([option, &class]);
+        }
+        class
+    });
+    command.args(args);
+
+    let mut child = command.spawn()?;
+    let pid = child.id();
+    let found = wait::wait_until(
+        || match &class {
+            // Anchored, so that e.g. `xdotool-42-1` doesn't match the window of `xdotool-42-10`
+            Some(class) => SearchQuery::new()
+                .class(&format!("^{}$", SearchQuery::literal(class)))
+                .only_visible()
+                .run(),
+            None => windows_of(pid),
+        },
+        |windows| !windows.is_empty(),
+        &Backoff::with_timeout(options.timeout),
+    );
+
+    match found {
+        Ok(windows) => Ok((child, Window::new(windows[0]))),
+        Err(e) => {
+            let _ = child.kill();
+            let _ = child.wait();
+            Err(e)
+        }
+    }
+}
+
+/// The visible windows of `pid` or, if it has none, of its descendants.
+fn windows_of(pid: u32) -> Result<Vec<WindowId>> {
+    let windows = SearchQuery::new().pid(pid).only_visible().run()?;
+    if !windows.is_empty() {
+        return Ok(windows);
+    }
+
+    let mut windows = Vec::new();
+    for pid in descendants(pid, &processes()) {
+        windows.extend(SearchQuery::new().pid(pid).only_visible().run()?);
+    }
+    Ok(windows)
+}
+
+/// The pid and parent pid of every process, read from `/proc`.
+fn processes() -> Vec<(u32, u32)> {
+    let entries = match fs::read_dir("/proc") {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    entries
+        .filter_map(|entry| {
+            let pid = entry.ok()?.file_name().to_str()?.parse().ok()?;
+            let stat = fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+            Some((pid, parent_pid(&stat)?))
+        })
+        .collect()
+}
+
+/// Parse the parent pid from `/proc/<pid>/stat`. The command name may contain spaces and parentheses.
+fn parent_pid(stat: &str) -> Option<u32> {
+    let (_, fields) = stat.rsplit_once(')')?;
+    fields.split_whitespace().nth(1)?.parse().ok()
+}
+
+/// All descendants of `root`, breadth first.
+fn descendants(root: u32, processes: &[(u32, u32)]) -> Vec<u32> {
+    let mut found = vec![root];
+    let mut known: HashSet<u32> = found.iter().cloned().collect();
+    let mut i = 0;
+
+    while i < found.len() {
+        let parent = found[i];
+        for (pid, _) in processes.iter().filter(|(_, ppid)| *ppid == parent) {
+            if known.insert(*pid) {
+                found.push(*pid);
+            }
+        }
+        i += 1;
+    }
+    found.remove(0);
+    found
+}
+
+/// A number that is unique within this process.
+fn unique_suffix() -> usize {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    COUNTER.fetch_add(1, Ordering::SeqCst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{descendants, parent_pid};
+
+    #[test]
+    fn parent_pid_is_read_after_the_command_name() {
+        let stat = "4242 (my (weird) app) S 4200 4242 4242 0 -1 4194560";
+        assert_eq!(parent_pid(stat), Some(4200));
+    }
+
+    #[test]
+    fn descendants_include_grandchildren() {
+        let processes = [(1, 0), (10, 1), (11, 10), (12, 11), (20, 1), (13, 10)];
+        assert_eq!(descendants(10, &processes), vec![11, 13, 12]);
+        assert!(descendants(20, &processes).is_empty());
+    }
+}
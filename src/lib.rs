@@ -13,14 +13,24 @@
 use std::process::Command;
 use std::process::Output;
 
+pub mod chain;
 pub mod command;
+pub mod delivery;
 pub mod desktop;
+pub mod error;
 pub mod keyboard;
+pub mod launch;
 pub mod misc;
 pub mod mouse;
 pub mod optionvec;
+pub mod search;
+pub mod wait;
+pub mod watch;
 pub mod window;
 
+mod keysym;
+
+pub use error::{Error, Result};
 pub use optionvec::OptionVec;
 
 /// Execute a xdotool command.
@@ -45,12 +55,33 @@ pub use optionvec::OptionVec;
 /// ```
 pub fn run(command: command::Command, args: &str) -> Output {
     let cmd = format!("xdotool {} {}", command, args);
+    shell(&cmd)
+}
 
+/// Execute a command line with `sh`.
+pub(crate) fn shell(cmd: &str) -> Output {
     Command::new("sh")
         .arg("-c")
         .arg(cmd)
         .output()
-        .unwrap_or_else(|_| panic!("Failed to execute 'xdotool key {}", args))
+        .unwrap_or_else(|_| panic!("Failed to execute '{}'", cmd))
+}
+
+/// Quote an argument so `sh` passes it to the command verbatim.
+pub(crate) fn quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
+/// Get the standard output of a command as a `String`, or an error if the command failed.
+pub(crate) fn stdout(output: Output) -> Result<String> {
+    if !output.status.success() {
+        return Err(Error::Failed {
+            status: output.status.code(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        });
+    }
+
+    String::from_utf8(output.stdout).map_err(|e| Error::Parse(e.to_string()))
 }
 
 #[cfg(test)]
@@ -59,4 +90,11 @@ mod tests {
     fn it_works() {
         assert_eq!(2 + 2, 4);
     }
+
+    #[test]
+    fn quote_survives_the_shell() {
+        let arg = "it's -- $HOME `ls` \"x\"";
+        let output = super::shell(&format!("printf %s {}", super::quote(arg)));
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), arg);
+    }
 }
@@ -1,25 +1,137 @@
 //! Convenience functions for miscellaneous functionality in xdotool.
 
-use std::process::Output;
+use std::path::PathBuf;
+use std::process::{self, Child, ExitStatus, Output};
 
-use crate::command::options::SyncOption;
+use crate::command::options::ExecOption;
 use crate::command::{sub_commands, Command};
+use crate::error::Result;
 use crate::optionvec::OptionVec;
-use crate::run;
+use crate::{quote, run};
+
+/// A program executed by [`exec`](fn.exec.html), with its arguments passed as they are instead of through a command line.
+///
+/// # Examples
+///
+/// Turn off the screen as part of a chain, which can continue after `exec`:
+///
+/// ```
+/// # use xdotool::chain::Chain;
+/// # use xdotool::misc::ExecSpec;
+/// let mut lock = ExecSpec::new("xset");
+/// lock.args = vec!["dpms".to_owned(), "force".to_owned(), "off".to_owned()];
+/// let (exec, args) = lock.command();
+/// assert_eq!(args, "'xset' 'dpms' 'force' 'off'");
+///
+/// let chain = Chain::new().then(exec, &args);
+/// assert_eq!(chain.to_string(), "exec --args 4 'xset' 'dpms' 'force' 'off'");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExecSpec {
+    pub program: String,
+    pub args: Vec<String>,
+    /// Additional environment variables. They are set for the xdotool process and inherited by the program.
+    pub env: Vec<(String, String)>,
+    /// The working directory of the xdotool process and the program.
+    pub cwd: Option<PathBuf>,
+    /// Block until the program exits. xdotool then exits with the status of the program.
+    pub sync: bool,
+    /// Mark the end of the arguments with this word instead of passing their number with `--args`.
+    pub terminator: Option<String>,
+}
+
+impl ExecSpec {
+    pub fn new(program: &str) -> ExecSpec {
+        ExecSpec {
+            program: program.to_owned(),
+            ..ExecSpec::default()
+        }
+    }
+
+    /// The `exec` command and its arguments, e.g. for a [`Chain`](../chain/struct.Chain.html).
+    ///
+    /// Without `--args` or `--terminator`, `exec` would consume all remaining arguments of a chain,
+    /// so the number of arguments (including the program) is always passed unless there is a terminator.
+    pub fn command(&self) -> (Command, String) {
+        let mut options = OptionVec::new();
+        if self.sync {
+            options.0.push(ExecOption::Sync);
+        }
+        match &self.terminator {
+            Some(terminator) => options.0.push(ExecOption::Terminator(quote(terminator))),
+            None => options.0.push(ExecOption::Args(self.args.len() + 1)),
+        }
+
+        let mut args: Vec<String> = std::iter::once(&self.program)
+            .chain(&self.args)
+            .map(|arg| quote(arg))
+            .collect();
+        if let Some(terminator) = &self.terminator {
+            args.push(quote(terminator));
+        }
+
+        (
+            Command::Misc(sub_commands::Misc::Exec(options)),
+            args.join(" "),
+        )
+    }
+}
+
+/// The result of [`exec`](fn.exec.html).
+#[derive(Debug)]
+pub enum Exec {
+    /// The exit status of the program, with `sync`.
+    Status(ExitStatus),
+    /// The running xdotool process, which waits for the program and exits with its status.
+    Running(Child),
+}
 
 /// Execute a program. This is often useful when combined with [`mouse::behave_screen_edge`](../mouse/fn.behave_screen_edge.html) to do things like locking your screen.
-/// 
-/// # Options
 ///
-/// - `SyncOption::Sync` Block until the child process exits. The child process status is then passed to the parent process which copies it.
-/// 
+/// With `sync` this blocks until the program exits and returns its exit status.
+/// Otherwise it returns a handle of the xdotool process, which is kept alive until the program exits.
+/// Killing the handle doesn't kill the program.
+///
 /// # Examples
+///
+/// Run `make` in another directory and check whether it succeeded:
+///
+/// ```no_run
+/// # use xdotool::misc::{self, Exec, ExecSpec};
+/// let spec = ExecSpec {
+///     args: vec!["-j4".to_owned()],
+///     cwd: Some("/tmp/project".into()),
+///     env: vec![("CC".to_owned(), "clang".to_owned())],
+///     sync: true,
+///     ..ExecSpec::new("make")
+/// };
+/// if let Exec::Status(status) = misc::exec(&spec)? {
+///     assert!(status.success());
+/// }
+/// # Ok::<(), xdotool::Error>(())
+/// ```
+pub fn exec(spec: &ExecSpec) -> Result<Exec> {
+    // Without --sync xdotool exits right away, so the handle would be useless
+    let (c, args) = ExecSpec {
+        sync: true,
+        ..spec.clone()
+    }
+    .command();
 
-// TODO: Check if this function has the desired behaviour as described in the man page
+    let mut command = process::Command::new("sh");
+    command
+        .arg("-c")
+        .arg(format!("xdotool {} {}", c, args))
+        .envs(spec.env.iter().map(|(k, v)| (k, v)));
+    if let Some(cwd) = &spec.cwd {
+        command.current_dir(cwd);
+    }
 
-pub fn exec(command: &str, options: OptionVec<SyncOption>) -> Output {
-    let c = Command::Misc(sub_commands::Misc::Exec(options));
-    run(c, command)
+    if spec.sync {
+        Ok(Exec::Status(command.status()?))
+    } else {
+        Ok(Exec::Running(command.spawn()?))
+    }
 }
 
 /// Sleep for a specified period. Fractions of seconds (like 1.3 or 0.4) are valid.
@@ -27,3 +139,36 @@ pub fn sleep(seconds: f32) -> Output {
     let c = Command::Misc(sub_commands::Misc::Sleep);
     run(c, &seconds.to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::ExecSpec;
+
+    fn render(spec: &ExecSpec) -> String {
+        let (command, args) = spec.command();
+        format!("{} {}", command, args)
+    }
+
+    #[test]
+    fn exec_passes_the_number_of_arguments() {
+        let mut spec = ExecSpec::new("notify-send");
+        assert_eq!(render(&spec), "exec --args 1 'notify-send'");
+
+        spec.args = vec!["it's done".to_owned(), "$HOME; rm -rf /".to_owned()];
+        spec.sync = true;
+        assert_eq!(
+            render(&spec),
+            "exec --sync --args 3 'notify-send' 'it'\\''s done' '$HOME; rm -rf /'"
+        );
+    }
+
+    #[test]
+    fn exec_ends_with_the_terminator() {
+        let spec = ExecSpec {
+            args: vec!["-l".to_owned()],
+            terminator: Some("END".to_owned()),
+            ..ExecSpec::new("ls")
+        };
+        assert_eq!(render(&spec), "exec --terminator 'END' 'ls' '-l' 'END'");
+    }
+}
@@ -1,14 +1,20 @@
 //! Convenience functions for the mouse functionality in xdotool.
 
 use std::fmt;
-use std::process::Output;
+use std::process::{self, Output};
+use std::str::FromStr;
+use std::time::Duration;
 
+use crate::chain::Chain;
 use crate::command::options::{
-    BehaveScreenEdgeOption, ClickOption, MouseMoveOption, MouseMoveRelativeOption,
+    BehaveScreenEdgeOption, ClickOption, MouseMoveOption, MouseMoveRelativeOption, SyncOption,
 };
 use crate::command::{sub_commands, Command};
+use crate::delivery::{deliver, Delivery};
+use crate::error::{Error, Result};
 use crate::optionvec::OptionVec;
-use crate::run;
+use crate::window::WindowId;
+use crate::{desktop, keyboard, option_vec, run, stdout};
 
 /// Move the mouse to the specific x and y coordinates on the screen
 ///
@@ -100,6 +106,35 @@ pub fn click(button: Button, options: OptionVec<ClickOption>) -> Output {
     run(c, &button.to_string())
 }
 
+/// Same as [`click`](fn.click.html), but delivers the click to `window` as described by `delivery`.
+/// The click happens at the current mouse position.
+///
+/// If `restore` is set, the previously active window is activated again afterwards.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use xdotool::delivery::Delivery;
+/// # use xdotool::mouse::{self, Button};
+/// # use xdotool::OptionVec;
+/// mouse::click_to("62914567", Button::Left, OptionVec::new(), &Delivery::ActivateThenXTest, true)?;
+/// # Ok::<(), xdotool::Error>(())
+/// ```
+pub fn click_to(
+    window: &str,
+    button: Button,
+    mut options: OptionVec<ClickOption>,
+    delivery: &Delivery,
+    restore: bool,
+) -> Result<()> {
+    deliver(window, delivery, restore, |send_event| {
+        if send_event {
+            options.0.push(ClickOption::Window(window.to_owned()));
+        }
+        stdout(click(button, options)).map(|_| ())
+    })
+}
+
 /// Same as [`click`](fn.click.html), except only a mouse down is sent.
 ///
 /// # Options
@@ -126,6 +161,247 @@ pub fn click_up(button: Button, options: OptionVec<ClickOption>) -> Output {
     run(c, &button.to_string())
 }
 
+/// Hold the given button for `duration`, then release it.
+///
+/// The mouse down, the wait and the mouse up are executed by a single xdotool process,
+/// so the button is held for exactly `duration` without the latency of spawning a second process.
+///
+/// # Examples
+///
+/// Long-press the left button to open a context menu on a touch-oriented application:
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use xdotool::mouse::{self, Button};
+/// let output = mouse::press_for(Button::Left, Duration::from_millis(800));
+/// ```
+pub fn press_for(button: Button, duration: Duration) -> Output {
+    Chain::new()
+        .then(
+            Command::Mouse(sub_commands::Mouse::MouseDown(OptionVec::new())),
+            &button.to_string(),
+        )
+        .then(
+            Command::Misc(sub_commands::Misc::Sleep),
+            &duration.as_secs_f64().to_string(),
+        )
+        .then(
+            Command::Mouse(sub_commands::Mouse::MouseUp(OptionVec::new())),
+            &button.to_string(),
+        )
+        .run()
+}
+
+/// Read the double-click interval of the desktop from the `Net/DoubleClickTime` XSETTINGS property.
+///
+/// The property is read with `dump_xsettings`, which is part of xsettingsd.
+/// Returns `None` if the tool is not installed, no XSETTINGS manager is running or the property is not set.
+///
+/// # Examples
+///
+/// ```
+/// # use std::time::Duration;
+/// # use xdotool::mouse;
+/// let interval = mouse::double_click_time().unwrap_or(Duration::from_millis(400));
+/// ```
+pub fn double_click_time() -> Option<Duration> {
+    let output = process::Command::new("dump_xsettings").output().ok()?;
+    parse_double_click_time(&stdout(output).ok()?)
+}
+
+/// Find `Net/DoubleClickTime` in the output of `dump_xsettings`, which prints one `name value` pair per line.
+fn parse_double_click_time(settings: &str) -> Option<Duration> {
+    settings
+        .lines()
+        .filter_map(|line| {
+            let mut kv = line.split_whitespace();
+            match (kv.next(), kv.next()) {
+                (Some("Net/DoubleClickTime"), Some(v)) => v.parse().ok(),
+                _ => None,
+            }
+        })
+        .next()
+        .map(Duration::from_millis)
+}
+
+/// Send a double-click with the given button to the current mouse position.
+///
+/// The delay between the clicks is derived from [`double_click_time`](fn.double_click_time.html).
+/// If the interval can't be read, `fallback` is used instead.
+/// Returns `Error::PointerMoved` if the pointer was moved while clicking.
+///
+/// # Examples
+///
+/// Select the word under the pointer:
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use xdotool::mouse::{self, Button};
+/// mouse::double_click(Button::Left, Duration::from_millis(400))?;
+/// # Ok::<(), xdotool::Error>(())
+/// ```
+pub fn double_click(button: Button, fallback: Duration) -> Result<()> {
+    multi_click(button, 2, fallback)
+}
+
+/// Same as [`double_click`](fn.double_click.html), but sends three clicks, e.g. to select a whole line.
+pub fn triple_click(button: Button, fallback: Duration) -> Result<()> {
+    multi_click(button, 3, fallback)
+}
+
+/// Click `count` times within the double-click interval and validate that the pointer didn't move.
+fn multi_click(button: Button, count: u32, fallback: Duration) -> Result<()> {
+    let interval = double_click_time().unwrap_or(fallback);
+    // Stay well within the interval, since every click takes some time on its own
+    let delay = interval.as_millis() as u32 / 4;
+
+    let output = Chain::new()
+        .then(Command::Mouse(sub_commands::Mouse::GetMouseLocation), "")
+        .then(
+            Command::Mouse(sub_commands::Mouse::Click(option_vec![
+                ClickOption::Repeat(count),
+                ClickOption::Delay(delay)
+            ])),
+            &button.to_string(),
+        )
+        .then(Command::Mouse(sub_commands::Mouse::GetMouseLocation), "")
+        .run();
+
+    let locations = stdout(output)?
+        .lines()
+        .map(str::parse)
+        .collect::<Result<Vec<Location>>>()?;
+    check_unmoved(&locations)
+}
+
+/// Check that the first and last of the `locations` read around a multi-click are the same.
+fn check_unmoved(locations: &[Location]) -> Result<()> {
+    match (locations.first(), locations.last()) {
+        (Some(from), Some(to)) if (from.x, from.y, from.screen) != (to.x, to.y, to.screen) => {
+            Err(Error::PointerMoved {
+                from: *from,
+                to: *to,
+            })
+        }
+        (Some(_), Some(_)) => Ok(()),
+        _ => Err(Error::Parse("missing mouse location".to_owned())),
+    }
+}
+
+/// Select text by dragging with the left button from `from` to `to`.
+/// Both positions are relative to the top left corner of `window`.
+///
+/// If anything fails while the button is held, the button is released again.
+/// Nothing is released if the chain fails before the button was pressed.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use xdotool::mouse;
+/// mouse::select_range("window-id", (40, 120), (300, 180))?;
+/// # Ok::<(), xdotool::Error>(())
+/// ```
+pub fn select_range(window: &str, from: (u16, u16), to: (u16, u16)) -> Result<()> {
+    let output = drag_chain(window, from, to).run();
+    if !output.status.success() && !output.stdout.is_empty() {
+        click_up(Button::Left, OptionVec::new());
+    }
+    stdout(output).map(|_| ())
+}
+
+/// The chain of [`select_range`](fn.select_range.html).
+///
+/// The location is printed right after the button is pressed,
+/// so the output of a failed chain shows whether the button is still held.
+fn drag_chain(window: &str, from: (u16, u16), to: (u16, u16)) -> Chain {
+    Chain::new()
+        .then(move_to(window), &format!("{} {}", from.0, from.1))
+        .then(
+            Command::Mouse(sub_commands::Mouse::MouseDown(OptionVec::new())),
+            &Button::Left.to_string(),
+        )
+        .then(Command::Mouse(sub_commands::Mouse::GetMouseLocation), "")
+        .then(move_to(window), &format!("{} {}", to.0, to.1))
+        .then(
+            Command::Mouse(sub_commands::Mouse::MouseUp(OptionVec::new())),
+            &Button::Left.to_string(),
+        )
+}
+
+/// Select text by clicking at `from` and shift-clicking at `to`.
+/// Both positions are relative to the top left corner of `window`.
+///
+/// If anything fails while shift is held, the key is released again.
+/// Nothing is released if the chain fails before shift was pressed.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use xdotool::mouse;
+/// mouse::select_by_shift_click("window-id", (40, 120), (300, 180))?;
+/// # Ok::<(), xdotool::Error>(())
+/// ```
+pub fn select_by_shift_click(window: &str, from: (u16, u16), to: (u16, u16)) -> Result<()> {
+    let output = shift_click_chain(window, from, to).run();
+    if !output.status.success() && !output.stdout.is_empty() {
+        keyboard::send_key_up("shift", OptionVec::new());
+    }
+    stdout(output).map(|_| ())
+}
+
+/// The chain of [`select_by_shift_click`](fn.select_by_shift_click.html).
+///
+/// The location is printed right after shift is pressed,
+/// so the output of a failed chain shows whether shift is still held.
+fn shift_click_chain(window: &str, from: (u16, u16), to: (u16, u16)) -> Chain {
+    Chain::new()
+        .then(move_to(window), &format!("{} {}", from.0, from.1))
+        .then(
+            Command::Mouse(sub_commands::Mouse::Click(OptionVec::new())),
+            &Button::Left.to_string(),
+        )
+        .then(
+            Command::Keyboard(sub_commands::Keyboard::KeyDown(OptionVec::new())),
+            "shift",
+        )
+        .then(Command::Mouse(sub_commands::Mouse::GetMouseLocation), "")
+        .then(move_to(window), &format!("{} {}", to.0, to.1))
+        .then(
+            Command::Mouse(sub_commands::Mouse::Click(OptionVec::new())),
+            &Button::Left.to_string(),
+        )
+        .then(
+            Command::Keyboard(sub_commands::Keyboard::KeyUp(OptionVec::new())),
+            "shift",
+        )
+}
+
+/// Select the word at the given position of `window` with a double-click.
+/// See [`double_click`](fn.double_click.html) for the meaning of `fallback`.
+pub fn select_word(window: &str, at: (u16, u16), fallback: Duration) -> Result<()> {
+    stdout(move_mouse(at.0, at.1, move_options(window)))?;
+    double_click(Button::Left, fallback)
+}
+
+/// Select the line at the given position of `window` with a triple-click.
+/// See [`double_click`](fn.double_click.html) for the meaning of `fallback`.
+pub fn select_line(window: &str, at: (u16, u16), fallback: Duration) -> Result<()> {
+    stdout(move_mouse(at.0, at.1, move_options(window)))?;
+    triple_click(Button::Left, fallback)
+}
+
+/// A mouse move relative to `window` that waits until the pointer arrived.
+fn move_to(window: &str) -> Command {
+    Command::Mouse(sub_commands::Mouse::MouseMove(move_options(window)))
+}
+
+fn move_options(window: &str) -> OptionVec<MouseMoveOption> {
+    option_vec![
+        MouseMoveOption::Window(window.to_owned()),
+        MouseMoveOption::Sync
+    ]
+}
+
 /// Outputs the x, y, screen, and window id of the mouse cursor. Screen numbers will be nonzero if you have multiple monitors and are not using Xinerama.
 ///
 /// # Examples
@@ -144,6 +420,105 @@ pub fn get_mouse_location() -> Output {
     run(c, "")
 }
 
+/// Same as [`get_mouse_location`](fn.get_mouse_location.html), but parses the output into a [`Location`](struct.Location.html).
+///
+/// # Examples
+///
+/// ```no_run
+/// # use xdotool::mouse;
+/// let location = mouse::location()?;
+/// println!("The pointer is at {}x{}", location.x, location.y);
+/// # Ok::<(), xdotool::Error>(())
+/// ```
+pub fn location() -> Result<Location> {
+    stdout(get_mouse_location())?.parse()
+}
+
+/// Run `f` and move the mouse back to where it was before, even if `f` fails or panics.
+///
+/// The location is read with [`get_mouse_location`](fn.get_mouse_location.html) before `f` runs
+/// and restored with `MouseMoveOption::Screen` afterwards.
+/// The value returned by `f` is passed through, so errors of `f` are not lost.
+/// Restoring is best effort: if it fails, the value of `f` is still returned.
+///
+/// # Examples
+///
+/// Click a tray icon and leave the pointer where the user had it:
+///
+/// ```no_run
+/// # use xdotool::mouse::{self, Button};
+/// # use xdotool::OptionVec;
+/// mouse::preserve_pointer(|| {
+///     mouse::move_mouse(1900, 10, OptionVec::new());
+///     mouse::click(Button::Left, OptionVec::new());
+/// })?;
+/// # Ok::<(), xdotool::Error>(())
+/// ```
+pub fn preserve_pointer<T, F: FnOnce() -> T>(f: F) -> Result<T> {
+    let _guard = RestoreGuard::new(false)?;
+    Ok(f())
+}
+
+/// Same as [`preserve_pointer`](fn.preserve_pointer.html), but also activates the previously active window afterwards.
+///
+/// The active window is read with [`desktop::get_active_window`](../desktop/fn.get_active_window.html).
+/// If there is no active window, only the pointer is restored.
+/// The pointer is moved back even if the window can't be activated, e.g. because it was closed.
+pub fn preserve_pointer_and_focus<T, F: FnOnce() -> T>(f: F) -> Result<T> {
+    let _guard = RestoreGuard::new(true)?;
+    Ok(f())
+}
+
+/// Restores the pointer (and optionally the active window) when dropped.
+struct RestoreGuard {
+    location: Location,
+    window: Option<WindowId>,
+}
+
+impl RestoreGuard {
+    fn new(focus: bool) -> Result<RestoreGuard> {
+        let location = location()?;
+        let window = if focus {
+            stdout(desktop::get_active_window())
+                .ok()
+                .and_then(|s| s.parse().ok())
+        } else {
+            None
+        };
+
+        Ok(RestoreGuard { location, window })
+    }
+
+    /// Activate the window and move the pointer back. The pointer is moved even if the activation fails,
+    /// the first error is returned.
+    fn restore(&self) -> Result<()> {
+        let activated = match self.window {
+            Some(window) => stdout(desktop::activate_window(
+                &window.to_string(),
+                option_vec![SyncOption::Sync],
+            ))
+            .map(|_| ()),
+            None => Ok(()),
+        };
+
+        let moved = stdout(move_mouse(
+            self.location.x,
+            self.location.y,
+            option_vec![
+                MouseMoveOption::Screen(self.location.screen),
+                MouseMoveOption::Sync
+            ],
+        ));
+        activated.and(moved.map(|_| ()))
+    }
+}
+
+impl Drop for RestoreGuard {
+    fn drop(&mut self) {
+        let _ = self.restore();
+    }
+}
+
 /// Bind an action to events when the mouse hits the screen edge or corner.
 ///
 /// # Options
@@ -165,8 +540,6 @@ pub fn get_mouse_location() -> Output {
 ///     option_vec![options::BehaveScreenEdgeOption::Delay(500)]
 /// );
 /// ```
-
-
 // TODO: Fix the command argument. Currently it only supports commands without arguments and it's not possible to pass shell commands
 pub fn behave_screen_edge(
     screen_edge: ScreenEdge,
@@ -178,6 +551,44 @@ pub fn behave_screen_edge(
     run(bse_cmd, &args)
 }
 
+/// The location of the mouse cursor as reported by [`get_mouse_location`](fn.get_mouse_location.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    pub x: u16,
+    pub y: u16,
+    pub screen: u8,
+    /// The window under the cursor.
+    pub window: WindowId,
+}
+
+impl FromStr for Location {
+    type Err = Error;
+
+    /// Parse the output of `getmouselocation`, e.g. `x:640 y:360 screen:0 window:62914567`.
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || Error::Parse(format!("invalid mouse location '{}'", s.trim()));
+        let (mut x, mut y, mut screen, mut window) = (None, None, None, None);
+
+        for field in s.split_whitespace() {
+            let mut kv = field.splitn(2, ':');
+            match (kv.next(), kv.next()) {
+                (Some("x"), Some(v)) => x = v.parse().ok(),
+                (Some("y"), Some(v)) => y = v.parse().ok(),
+                (Some("screen"), Some(v)) => screen = v.parse().ok(),
+                (Some("window"), Some(v)) => window = v.parse().ok(),
+                _ => return Err(invalid()),
+            }
+        }
+
+        Ok(Location {
+            x: x.ok_or_else(invalid)?,
+            y: y.ok_or_else(invalid)?,
+            screen: screen.ok_or_else(invalid)?,
+            window: window.ok_or_else(invalid)?,
+        })
+    }
+}
+
 pub enum Button {
     Left,
     Middle,
@@ -188,7 +599,7 @@ pub enum Button {
 
 impl fmt::Display for Button {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match &*self {
+        match self {
             Button::Left => write!(f, "1"),
             Button::Middle => write!(f, "2"),
             Button::Right => write!(f, "3"),
@@ -211,7 +622,7 @@ pub enum ScreenEdge {
 
 impl fmt::Display for ScreenEdge {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match &*self {
+        match self {
             ScreenEdge::Left => write!(f, "left"),
             ScreenEdge::TopLeft => write!(f, "top-left"),
             ScreenEdge::Top => write!(f, "top"),
@@ -223,3 +634,77 @@ impl fmt::Display for ScreenEdge {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{check_unmoved, drag_chain, parse_double_click_time, shift_click_chain, Location};
+    use crate::error::Error;
+    use crate::window::WindowId;
+    use std::time::Duration;
+
+    #[test]
+    fn location_parses_getmouselocation_output() {
+        let location: Location = "x:640 y:360 screen:0 window:62914567\n".parse().unwrap();
+        assert_eq!(
+            location,
+            Location {
+                x: 640,
+                y: 360,
+                screen: 0,
+                window: WindowId(62914567),
+            }
+        );
+        assert!("x:640 y:360".parse::<Location>().is_err());
+    }
+
+    #[test]
+    fn double_click_time_is_read_from_xsettings() {
+        let settings = "Gtk/CursorThemeSize 24\n\
+                        Net/DoubleClickDistance 5\n\
+                        Net/DoubleClickTime 250\n\
+                        Net/ThemeName \"Adwaita\"\n";
+        assert_eq!(
+            parse_double_click_time(settings),
+            Some(Duration::from_millis(250))
+        );
+
+        assert_eq!(parse_double_click_time("Net/ThemeName \"Adwaita\"\n"), None);
+        assert_eq!(parse_double_click_time("Net/DoubleClickTime\n"), None);
+        assert_eq!(parse_double_click_time(""), None);
+    }
+
+    #[test]
+    fn drag_moves_relative_to_the_window_while_the_button_is_held() {
+        assert_eq!(
+            drag_chain("62914567", (40, 120), (300, 180)).to_string(),
+            "mousemove --window 62914567 --sync 40 120 mousedown  1 getmouselocation  \
+             mousemove --window 62914567 --sync 300 180 mouseup  1"
+        );
+    }
+
+    #[test]
+    fn shift_click_holds_shift_for_the_second_click() {
+        assert_eq!(
+            shift_click_chain("62914567", (40, 120), (300, 180)).to_string(),
+            "mousemove --window 62914567 --sync 40 120 click  1 keydown  shift getmouselocation  \
+             mousemove --window 62914567 --sync 300 180 click  1 keyup  shift"
+        );
+    }
+
+    #[test]
+    fn multi_click_fails_if_the_pointer_moved() {
+        let at = |x| Location {
+            x,
+            y: 360,
+            screen: 0,
+            window: WindowId(62914567),
+        };
+
+        assert!(check_unmoved(&[at(640), at(640)]).is_ok());
+        assert!(matches!(
+            check_unmoved(&[at(640), at(700)]),
+            Err(Error::PointerMoved { from, to }) if from.x == 640 && to.x == 700
+        ));
+        assert!(matches!(check_unmoved(&[]), Err(Error::Parse(_))));
+    }
+}
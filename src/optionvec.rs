@@ -9,9 +9,9 @@ use std::fmt;
 /// ```
 /// # use xdotool::{OptionVec, option_vec};
 /// # use xdotool::command::options;
-/// let o1 = OptionVec::<options::KeyboardOption>::new();
+/// let o1 = OptionVec::<options::KeyOption>::new();
 /// // Or
-/// let o2: OptionVec<options::KeyboardOption> = option_vec![];
+/// let o2: OptionVec<options::KeyOption> = option_vec![];
 ///
 /// ```
 ///
@@ -27,7 +27,7 @@ use std::fmt;
 ///     options::SearchOption::Any,
 /// ];
 /// ```
-#[derive(Debug, Default)]
+#[derive(Debug, Clone)]
 pub struct OptionVec<T: fmt::Display>(pub Vec<T>);
 
 impl<T: fmt::Display> OptionVec<T> {
@@ -36,6 +36,12 @@ impl<T: fmt::Display> OptionVec<T> {
     }
 }
 
+impl<T: fmt::Display> Default for OptionVec<T> {
+    fn default() -> OptionVec<T> {
+        OptionVec::new()
+    }
+}
+
 impl<T: fmt::Display> fmt::Display for OptionVec<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let mut space_separated = String::new();
@@ -43,7 +49,7 @@ impl<T: fmt::Display> fmt::Display for OptionVec<T> {
         if !self.0.is_empty() {
             for thing in &self.0[0..self.0.len() - 1] {
                 space_separated.push_str(&thing.to_string());
-                space_separated.push_str(" ");
+                space_separated.push(' ');
             }
 
             space_separated.push_str(&self.0[self.0.len() - 1].to_string());
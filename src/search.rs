@@ -0,0 +1,536 @@
+//! A typed builder for the search command of xdotool.
+
+use std::collections::HashSet;
+
+use crate::command::options::SearchOption;
+use crate::error::{Error, Result};
+use crate::optionvec::OptionVec;
+use crate::window::{self, WindowId};
+use crate::{quote, stdout};
+
+/// Build a window search with validated patterns and typed criteria.
+///
+/// xdotool matches patterns as case-insensitive POSIX extended regular expressions.
+/// The patterns are validated before xdotool is called, and [`literal`](#method.literal) escapes plain text.
+///
+/// Every field can have its own pattern. Since xdotool applies a single pattern to all selected fields,
+/// [`run`](#method.run) executes one search per distinct pattern and combines the results:
+/// by default, and with [`all`](#method.all), a window has to match every field, whether or not the fields share a pattern;
+/// with [`any`](#method.any) one is enough.
+///
+/// # Examples
+///
+/// Search for a visible window with an exact title on desktop 1:
+///
+/// ```no_run
+/// # use xdotool::search::SearchQuery;
+/// let windows = SearchQuery::new()
+///     .name(&SearchQuery::literal("Report (draft) [1].pdf"))
+///     .desktop(1)
+///     .only_visible()
+///     .run()?;
+/// # Ok::<(), xdotool::Error>(())
+/// ```
+///
+/// Search for a terminal whose title contains "vim":
+///
+/// ```no_run
+/// # use xdotool::search::SearchQuery;
+/// let windows = SearchQuery::new().name("vim").class("^xterm$").run()?;
+/// # Ok::<(), xdotool::Error>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchQuery {
+    name: Option<String>,
+    class: Option<String>,
+    classname: Option<String>,
+    role: Option<String>,
+    title: Option<String>,
+    desktop: Option<u32>,
+    pid: Option<u32>,
+    screen: Option<u8>,
+    max_depth: Option<i32>,
+    limit: Option<u32>,
+    only_visible: bool,
+    all: bool,
+    any: bool,
+    sync: bool,
+}
+
+impl SearchQuery {
+    /// Create an empty query. Without a matcher xdotool matches the name, class and class name.
+    pub fn new() -> SearchQuery {
+        SearchQuery::default()
+    }
+
+    /// Escape all regular expression metacharacters, so `text` is matched literally.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use xdotool::search::SearchQuery;
+    /// assert_eq!(SearchQuery::literal("a (b).txt"), r"a \(b\)\.txt");
+    /// ```
+    pub fn literal(text: &str) -> String {
+        let mut escaped = String::with_capacity(text.len());
+        for c in text.chars() {
+            if "\\^$.|?*+()[]{}".contains(c) {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+        }
+        escaped
+    }
+
+    /// Match `pattern` against the window name, which is usually the title.
+    pub fn name(mut self, pattern: &str) -> SearchQuery {
+        self.name = Some(pattern.to_owned());
+        self
+    }
+
+    /// Match `pattern` against the window class.
+    pub fn class(mut self, pattern: &str) -> SearchQuery {
+        self.class = Some(pattern.to_owned());
+        self
+    }
+
+    /// Match `pattern` against the window class name.
+    pub fn classname(mut self, pattern: &str) -> SearchQuery {
+        self.classname = Some(pattern.to_owned());
+        self
+    }
+
+    /// Match `pattern` against the window role (WM_WINDOW_ROLE).
+    pub fn role(mut self, pattern: &str) -> SearchQuery {
+        self.role = Some(pattern.to_owned());
+        self
+    }
+
+    /// Match `pattern` against the window title using xdotool's legacy `--title` option.
+    pub fn title(mut self, pattern: &str) -> SearchQuery {
+        self.title = Some(pattern.to_owned());
+        self
+    }
+
+    /// Only match windows on the given desktop.
+    pub fn desktop(mut self, desktop: u32) -> SearchQuery {
+        self.desktop = Some(desktop);
+        self
+    }
+
+    /// Only match windows that belong to the given process id.
+    pub fn pid(mut self, pid: u32) -> SearchQuery {
+        self.pid = Some(pid);
+        self
+    }
+
+    /// Only match windows on the given screen.
+    pub fn screen(mut self, screen: u8) -> SearchQuery {
+        self.screen = Some(screen);
+        self
+    }
+
+    /// Set the recursion depth. -1 means infinite, 0 means only root windows.
+    pub fn max_depth(mut self, depth: i32) -> SearchQuery {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Stop searching after finding `limit` matching windows.
+    pub fn limit(mut self, limit: u32) -> SearchQuery {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Only match visible windows.
+    pub fn only_visible(mut self) -> SearchQuery {
+        self.only_visible = true;
+        self
+    }
+
+    /// Require that all criteria match. This is the default. Can't be combined with [`any`](#method.any).
+    pub fn all(mut self) -> SearchQuery {
+        self.all = true;
+        self
+    }
+
+    /// Match windows that match any criterion. Can't be combined with [`all`](#method.all).
+    pub fn any(mut self) -> SearchQuery {
+        self.any = true;
+        self
+    }
+
+    /// Block until there are results.
+    pub fn sync(mut self) -> SearchQuery {
+        self.sync = true;
+        self
+    }
+
+    /// Validate the query and turn it into the pattern and options for [`window::search`](../window/fn.search.html).
+    ///
+    /// The pattern is quoted for the shell that runs xdotool, or empty if the query has no pattern.
+    /// Fails for queries with different patterns for different fields, since they need more than one search.
+    pub fn build(&self) -> Result<(String, OptionVec<SearchOption>)> {
+        let mut searches = self.searches(true)?;
+        if searches.len() > 1 {
+            return Err(Error::InvalidQuery(
+                "different patterns per field need more than one search, use `run`".to_owned(),
+            ));
+        }
+        let (pattern, options) = searches.pop().unwrap_or_default();
+        Ok((quote_pattern(&pattern), options))
+    }
+
+    /// Run the search and return the matching windows. No match is not an error.
+    pub fn run(&self) -> Result<Vec<WindowId>> {
+        let searches = self.searches(false)?;
+        let mut windows: Option<Vec<WindowId>> = None;
+
+        for (pattern, options) in searches {
+            let found = parse_search_output(window::search(&quote_pattern(&pattern), options))?;
+
+            windows = Some(match windows {
+                None => found,
+                Some(mut windows) if self.any => {
+                    let known: HashSet<WindowId> = windows.iter().cloned().collect();
+                    windows.extend(found.into_iter().filter(|w| !known.contains(w)));
+                    windows
+                }
+                Some(windows) => {
+                    let found: HashSet<WindowId> = found.into_iter().collect();
+                    windows.into_iter().filter(|w| found.contains(w)).collect()
+                }
+            });
+        }
+
+        let mut windows = windows.unwrap_or_default();
+        if let Some(limit) = self.limit {
+            windows.truncate(limit as usize);
+        }
+        Ok(windows)
+    }
+
+    /// Validate the query and split it into one search per distinct pattern.
+    /// With `limit` the limit is passed to xdotool; otherwise it has to be applied to the combined results.
+    pub(crate) fn searches(&self, limit: bool) -> Result<Vec<(String, OptionVec<SearchOption>)>> {
+        if self.all && self.any {
+            return Err(Error::InvalidQuery(
+                "`all` and `any` are mutually exclusive".to_owned(),
+            ));
+        }
+
+        let matchers = [
+            (&self.name, SearchOption::Name),
+            (&self.class, SearchOption::Class),
+            (&self.classname, SearchOption::ClassName),
+            (&self.role, SearchOption::Role),
+            (&self.title, SearchOption::Title),
+        ];
+
+        // The fields of every distinct pattern, in the order of their first appearance
+        let mut groups: Vec<(&String, Vec<SearchOption>)> = Vec::new();
+        for (field, option) in matchers.iter() {
+            if let Some(p) = field {
+                validate_pattern(p)?;
+                match groups.iter_mut().find(|(pattern, _)| *pattern == p) {
+                    Some((_, fields)) => fields.push(option.clone()),
+                    None => groups.push((p, vec![option.clone()])),
+                }
+            }
+        }
+
+        let mut criteria = Vec::new();
+        if let Some(x) = self.desktop {
+            criteria.push(SearchOption::Desktop(x));
+        }
+        if let Some(x) = self.pid {
+            criteria.push(SearchOption::Pid(x));
+        }
+        if let Some(x) = self.screen {
+            criteria.push(SearchOption::Screen(x));
+        }
+        if let Some(x) = self.max_depth {
+            criteria.push(SearchOption::MaxDepth(x));
+        }
+        if let (Some(x), true) = (self.limit, limit || groups.len() <= 1) {
+            criteria.push(SearchOption::Limit(x));
+        }
+        if self.only_visible {
+            criteria.push(SearchOption::OnlyVisible);
+        }
+        // xdotool combines the fields of one search with OR by default, so ask for AND explicitly
+        if self.any {
+            criteria.push(SearchOption::Any);
+        } else {
+            criteria.push(SearchOption::All);
+        }
+        if self.sync {
+            criteria.push(SearchOption::Sync);
+        }
+
+        if groups.is_empty() {
+            return Ok(vec![(String::new(), OptionVec(criteria))]);
+        }
+
+        Ok(groups
+            .into_iter()
+            .map(|(pattern, mut options)| {
+                options.extend(criteria.iter().cloned());
+                (pattern.clone(), OptionVec(options))
+            })
+            .collect())
+    }
+}
+
+/// Quote `pattern` for the shell. An empty pattern is left out, so that xdotool doesn't get an empty argument.
+fn quote_pattern(pattern: &str) -> String {
+    if pattern.is_empty() {
+        String::new()
+    } else {
+        quote(pattern)
+    }
+}
+
+/// Parse the window ids printed by a search. xdotool exits with 1 if nothing matched.
+pub(crate) fn parse_search_output(output: std::process::Output) -> Result<Vec<WindowId>> {
+    if output.status.code() == Some(1) && output.stdout.is_empty() && output.stderr.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    stdout(output)?.lines().map(str::parse).collect()
+}
+
+/// Check that `pattern` is a valid POSIX extended regular expression.
+fn validate_pattern(pattern: &str) -> Result<()> {
+    let invalid = |reason: &str| {
+        Err(Error::InvalidQuery(format!(
+            "invalid pattern '{}': {}",
+            pattern, reason
+        )))
+    };
+
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut depth = 0;
+    // Whether a quantifier would have nothing to repeat at this point
+    let mut at_start = true;
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => {
+                if i + 1 == chars.len() {
+                    return invalid("trailing backslash");
+                }
+                i += 1;
+                at_start = false;
+            }
+            '[' => {
+                i += 1;
+                if chars.get(i) == Some(&'^') {
+                    i += 1;
+                }
+                // A leading ']' is part of the set
+                if chars.get(i) == Some(&']') {
+                    i += 1;
+                }
+                loop {
+                    match chars.get(i) {
+                        None => return invalid("unterminated bracket expression"),
+                        Some(']') => break,
+                        Some('[')
+                            if matches!(chars.get(i + 1), Some(':') | Some('.') | Some('=')) =>
+                        {
+                            let delimiter = chars[i + 1];
+                            i += 2;
+                            while !(chars.get(i) == Some(&delimiter)
+                                && chars.get(i + 1) == Some(&']'))
+                            {
+                                if i >= chars.len() {
+                                    return invalid("unterminated character class");
+                                }
+                                i += 1;
+                            }
+                            i += 2;
+                        }
+                        Some(_) => i += 1,
+                    }
+                }
+                at_start = false;
+            }
+            '(' => {
+                depth += 1;
+                at_start = true;
+            }
+            ')' => {
+                if depth == 0 {
+                    return invalid("unmatched parenthesis");
+                }
+                depth -= 1;
+                at_start = false;
+            }
+            '|' => at_start = true,
+            '*' | '+' | '?' => {
+                if at_start {
+                    return invalid("nothing to repeat");
+                }
+            }
+            '{' if chars.get(i + 1).is_some_and(|c| c.is_ascii_digit()) => {
+                if at_start {
+                    return invalid("nothing to repeat");
+                }
+                let end = match chars[i..].iter().position(|c| *c == '}') {
+                    Some(end) => i + end,
+                    None => return invalid("unterminated interval"),
+                };
+                let interval: String = chars[i + 1..end].iter().collect();
+                let mut bounds = interval.splitn(2, ',');
+                let min = bounds.next().and_then(|m| m.parse::<u32>().ok());
+                match (min, bounds.next()) {
+                    (Some(_), None) | (Some(_), Some("")) => {}
+                    (Some(min), Some(max)) => match max.parse::<u32>() {
+                        Ok(max) if min <= max => {}
+                        Ok(_) => return invalid("interval minimum is larger than maximum"),
+                        Err(_) => return invalid("malformed interval"),
+                    },
+                    (None, _) => return invalid("malformed interval"),
+                }
+                i = end;
+            }
+            '^' => at_start = true,
+            _ => at_start = false,
+        }
+        i += 1;
+    }
+
+    if depth != 0 {
+        return invalid("unmatched parenthesis");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{validate_pattern, SearchQuery};
+
+    #[test]
+    fn literal_patterns_are_valid_and_escaped() {
+        let literal = SearchQuery::literal("Report (draft) [1].pdf");
+        assert_eq!(literal, r"Report \(draft\) \[1\]\.pdf");
+        assert!(validate_pattern(&literal).is_ok());
+    }
+
+    #[test]
+    fn pattern_validation() {
+        for valid in &[
+            "firefox",
+            "^Mozilla (Firefox|Thunderbird)$",
+            "[]a-z[:digit:]]+",
+            "[^)]*",
+            "a{2,3}",
+            "a{2,}",
+            r"\(",
+            "",
+        ] {
+            assert!(validate_pattern(valid).is_ok(), "{}", valid);
+        }
+
+        for invalid in &[
+            "Report (draft",
+            "draft)",
+            "[1",
+            "[[:alpha:",
+            "*.pdf",
+            "(+a)",
+            "a{3,2}",
+            "a{2",
+            "a{1,x}",
+            "trailing\\",
+        ] {
+            assert!(validate_pattern(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn build_renders_typed_criteria() {
+        let (pattern, options) = SearchQuery::new()
+            .name("term")
+            .class("term")
+            .desktop(2)
+            .pid(42)
+            .limit(1)
+            .only_visible()
+            .all()
+            .build()
+            .unwrap();
+
+        assert_eq!(pattern, "'term'");
+        assert_eq!(
+            options.to_string(),
+            "--name --class --desktop 2 --pid 42 --limit 1 --onlyvisible --all"
+        );
+    }
+
+    #[test]
+    fn fields_sharing_a_pattern_must_all_match() {
+        let (pattern, options) = SearchQuery::new().name("x").class("x").build().unwrap();
+        assert_eq!(pattern, "'x'");
+        assert_eq!(options.to_string(), "--name --class --all");
+
+        let (_, options) = SearchQuery::new()
+            .name("x")
+            .class("x")
+            .any()
+            .build()
+            .unwrap();
+        assert_eq!(options.to_string(), "--name --class --any");
+    }
+
+    #[test]
+    fn built_pattern_survives_the_shell() {
+        let literal = SearchQuery::literal("Report (draft) [1].pdf");
+        let (pattern, _) = SearchQuery::new().name(&literal).build().unwrap();
+        let output = crate::shell(&format!("printf %s {}", pattern));
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), literal);
+
+        let (pattern, _) = SearchQuery::new().desktop(1).build().unwrap();
+        assert!(pattern.is_empty());
+    }
+
+    #[test]
+    fn build_rejects_invalid_queries() {
+        assert!(SearchQuery::new().any().all().build().is_err());
+        assert!(SearchQuery::new().name("(").build().is_err());
+        assert!(SearchQuery::new().name("a").class("b").build().is_err());
+    }
+
+    #[test]
+    fn different_patterns_are_split_into_searches() {
+        let searches = SearchQuery::new()
+            .name("vim")
+            .class("xterm")
+            .role("vim")
+            .title("xterm")
+            .desktop(1)
+            .limit(5)
+            .searches(false)
+            .unwrap();
+
+        let rendered: Vec<(String, String)> = searches
+            .into_iter()
+            .map(|(pattern, options)| (pattern, options.to_string()))
+            .collect();
+        assert_eq!(
+            rendered,
+            vec![
+                (
+                    "vim".to_owned(),
+                    "--name --role --desktop 1 --all".to_owned()
+                ),
+                (
+                    "xterm".to_owned(),
+                    "--class --title --desktop 1 --all".to_owned()
+                ),
+            ]
+        );
+    }
+}
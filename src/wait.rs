@@ -0,0 +1,258 @@
+//! Wait for windows and conditions with a timeout.
+//!
+//! `SearchOption::Sync` blocks until a window matches, but without a timeout.
+//! These functions poll instead, with an exponential [`Backoff`](struct.Backoff.html) between the checks,
+//! and return [`Error::Timeout`](../error/enum.Error.html#variant.Timeout) with the last observed state when giving up.
+
+use std::fmt;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::command::options::GetWindowGeometryOption;
+use crate::error::{Error, Result};
+use crate::optionvec::OptionVec;
+use crate::search::SearchQuery;
+use crate::window::{self, Geometry, WindowId};
+use crate::{desktop, option_vec, stdout};
+
+/// The delays between two checks of a wait condition.
+///
+/// The first delay is `initial`, every following one is multiplied by `factor` up to `max`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Backoff {
+    pub initial: Duration,
+    pub factor: u32,
+    pub max: Duration,
+    /// Give up after this time.
+    pub timeout: Duration,
+}
+
+impl Backoff {
+    /// The default backoff with another timeout.
+    pub fn with_timeout(timeout: Duration) -> Backoff {
+        Backoff {
+            timeout,
+            ..Backoff::default()
+        }
+    }
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Backoff {
+            initial: Duration::from_millis(20),
+            factor: 2,
+            max: Duration::from_millis(500),
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Observe a state until `done` accepts it, and return that state.
+///
+/// Errors of `observe` stop waiting immediately.
+/// On timeout, the last observed state is part of the error.
+///
+/// # Examples
+///
+/// Wait up to 5 seconds until at least 3 terminals are open:
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use xdotool::search::SearchQuery;
+/// # use xdotool::wait::{self, Backoff};
+/// let query = SearchQuery::new().class("^xterm$").only_visible();
+/// let terminals = wait::wait_until(
+///     || query.run(),
+///     |windows| windows.len() >= 3,
+///     &Backoff::with_timeout(Duration::from_secs(5)),
+/// )?;
+/// # Ok::<(), xdotool::Error>(())
+/// ```
+pub fn wait_until<S, O, D>(mut observe: O, mut done: D, backoff: &Backoff) -> Result<S>
+where
+    S: fmt::Debug,
+    O: FnMut() -> Result<S>,
+    D: FnMut(&S) -> bool,
+{
+    let start = Instant::now();
+    let mut delay = backoff.initial;
+
+    loop {
+        let state = observe()?;
+        if done(&state) {
+            return Ok(state);
+        }
+
+        let waited = start.elapsed();
+        if waited >= backoff.timeout {
+            return Err(Error::Timeout {
+                waited,
+                last: format!("{:?}", state),
+            });
+        }
+        thread::sleep(delay.min(backoff.timeout - waited));
+        delay = (delay * backoff.factor).min(backoff.max);
+    }
+}
+
+/// Wait until at least one window matches `query` and return the matching windows.
+pub fn wait_for_window(query: &SearchQuery, timeout: Duration) -> Result<Vec<WindowId>> {
+    wait_until(
+        || query.run(),
+        |windows| !windows.is_empty(),
+        &Backoff::with_timeout(timeout),
+    )
+}
+
+/// Wait until `window` is the active window.
+pub fn wait_for_active(window: WindowId, timeout: Duration) -> Result<()> {
+    wait_until(
+        || Ok(active_window()),
+        |active| *active == Some(window),
+        &Backoff::with_timeout(timeout),
+    )
+    .map(|_| ())
+}
+
+/// Wait until the name of `window` matches `pattern` and return the name.
+///
+/// The pattern is matched by xdotool like `SearchOption::Name`, i.e. as case-insensitive extended regular expression.
+///
+/// # Examples
+///
+/// Wait until a page finished loading, assuming the browser shows the page title:
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use xdotool::wait;
+/// # use xdotool::window::WindowId;
+/// let window: WindowId = "62914567".parse()?;
+/// let title = wait::wait_for_title_match(window, "^Inbox", Duration::from_secs(30))?;
+/// # Ok::<(), xdotool::Error>(())
+/// ```
+pub fn wait_for_title_match(window: WindowId, pattern: &str, timeout: Duration) -> Result<String> {
+    let query = SearchQuery::new().name(pattern);
+    wait_until(
+        || {
+            let title = stdout(window::get_window_name(&window.to_string()))?;
+            let matches = query.run()?.contains(&window);
+            Ok(Title {
+                title: title.trim_end_matches('\n').to_owned(),
+                matches,
+            })
+        },
+        |title| title.matches,
+        &Backoff::with_timeout(timeout),
+    )
+    .map(|title| title.title)
+}
+
+/// Wait until `window` no longer exists.
+pub fn wait_until_closed(window: WindowId, timeout: Duration) -> Result<()> {
+    wait_until(
+        || Ok(stdout(window::get_window_name(&window.to_string())).ok()),
+        |name| name.is_none(),
+        &Backoff::with_timeout(timeout),
+    )
+    .map(|_| ())
+}
+
+/// Wait until the geometry of `window` didn't change for `quiet`, e.g. after an animation, and return it.
+pub fn wait_for_geometry_stable(
+    window: WindowId,
+    quiet: Duration,
+    timeout: Duration,
+) -> Result<Geometry> {
+    let mut last: Option<(Geometry, Instant)> = None;
+    wait_until(
+        || {
+            let options = option_vec![GetWindowGeometryOption::Shell];
+            let geometry: Geometry =
+                stdout(window::get_window_geometry(&window.to_string(), options))?.parse()?;
+            let since = match last {
+                Some((previous, since)) if previous == geometry => since,
+                _ => Instant::now(),
+            };
+            last = Some((geometry, since));
+            Ok(Settling {
+                geometry,
+                unchanged_for: since.elapsed(),
+            })
+        },
+        |settling| settling.unchanged_for >= quiet,
+        &Backoff::with_timeout(timeout),
+    )
+    .map(|settling| settling.geometry)
+}
+
+/// The active window, or `None` if there is none, e.g. on an empty desktop.
+fn active_window() -> Option<WindowId> {
+    stdout(desktop::get_active_window())
+        .ok()
+        .and_then(|id| id.parse().ok())
+}
+
+#[derive(Debug)]
+struct Title {
+    title: String,
+    matches: bool,
+}
+
+#[derive(Debug)]
+struct Settling {
+    geometry: Geometry,
+    unchanged_for: Duration,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{wait_until, Backoff};
+    use crate::error::Error;
+    use std::time::Duration;
+
+    fn backoff(timeout_ms: u64) -> Backoff {
+        Backoff {
+            initial: Duration::from_millis(1),
+            factor: 2,
+            max: Duration::from_millis(4),
+            timeout: Duration::from_millis(timeout_ms),
+        }
+    }
+
+    #[test]
+    fn returns_the_accepted_state() {
+        let mut polls = 0;
+        let state = wait_until(
+            || {
+                polls += 1;
+                Ok(polls)
+            },
+            |polls| *polls == 3,
+            &backoff(1000),
+        );
+        assert_eq!(state.unwrap(), 3);
+    }
+
+    #[test]
+    fn timeout_reports_the_last_state() {
+        let result = wait_until(|| Ok("loading"), |_| false, &backoff(10));
+        match result {
+            Err(Error::Timeout { waited, last }) => {
+                assert!(waited >= Duration::from_millis(10));
+                assert_eq!(last, "\"loading\"");
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    #[test]
+    fn observation_errors_stop_waiting() {
+        let result: crate::Result<()> = wait_until(
+            || Err(Error::Parse("x".to_owned())),
+            |_| true,
+            &backoff(1000),
+        );
+        assert!(matches!(result, Err(Error::Parse(_))));
+    }
+}
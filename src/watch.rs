@@ -0,0 +1,308 @@
+//! Watch windows for changes.
+//!
+//! xdotool can't subscribe to X events, so the watcher polls [`window::list_all`](../window/fn.list_all.html)
+//! and the active window at a fixed interval and reports the differences between two snapshots.
+//! Changes that are reverted within one interval are not noticed.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::error::Result;
+use crate::search::SearchQuery;
+use crate::window::{self, Geometry, WindowId, WindowInfo};
+use crate::{desktop, stdout};
+
+/// A change between two snapshots of the watched windows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WindowEvent {
+    Created(WindowInfo),
+    Destroyed(WindowId),
+    TitleChanged {
+        id: WindowId,
+        old: String,
+        new: String,
+    },
+    GeometryChanged {
+        id: WindowId,
+        old: Geometry,
+        new: Geometry,
+    },
+    DesktopChanged {
+        id: WindowId,
+        old: Option<u32>,
+        new: Option<u32>,
+    },
+    /// The active window changed. It doesn't have to match the filter of the watcher.
+    ActiveChanged {
+        old: Option<WindowId>,
+        new: Option<WindowId>,
+    },
+}
+
+/// Polls the windows matching a filter and sends a [`WindowEvent`](enum.WindowEvent.html) for every change.
+///
+/// # Examples
+///
+/// Print the new title whenever a Firefox window changes its title:
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use xdotool::search::SearchQuery;
+/// # use xdotool::watch::{WindowEvent, WindowWatcher};
+/// let (events, handle) = WindowWatcher::new(SearchQuery::new().class("firefox"))
+///     .interval(Duration::from_millis(250))
+///     .start();
+///
+/// for event in events {
+///     if let WindowEvent::TitleChanged { new, .. } = event? {
+///         println!("{}", new);
+///     }
+/// }
+/// # handle.stop();
+/// # Ok::<(), xdotool::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct WindowWatcher {
+    filter: SearchQuery,
+    interval: Duration,
+}
+
+impl WindowWatcher {
+    /// Watch the windows matching `filter`, polling every 500ms.
+    pub fn new(filter: SearchQuery) -> WindowWatcher {
+        WindowWatcher {
+            filter,
+            interval: Duration::from_millis(500),
+        }
+    }
+
+    /// Set the time between two snapshots.
+    pub fn interval(mut self, interval: Duration) -> WindowWatcher {
+        self.interval = interval;
+        self
+    }
+
+    /// Start polling in a background thread.
+    ///
+    /// The windows existing at the start don't cause `Created` events.
+    /// Failed snapshots are sent as errors and the next snapshot is compared to the last successful one.
+    /// The thread stops when the [`WatchHandle`](struct.WatchHandle.html) is stopped or dropped, or the receiver is dropped.
+    pub fn start(self) -> (Receiver<Result<WindowEvent>>, WatchHandle) {
+        let (sender, receiver) = mpsc::channel();
+        let stopped = Arc::new(AtomicBool::new(false));
+        let stop = Arc::clone(&stopped);
+
+        let thread = thread::spawn(move || {
+            let mut last: Option<Snapshot> = None;
+
+            while !stop.load(Ordering::SeqCst) {
+                let events = match Snapshot::take(&self.filter) {
+                    Ok(snapshot) => {
+                        let events = last
+                            .as_ref()
+                            .map(|l| diff(l, &snapshot))
+                            .unwrap_or_default();
+                        last = Some(snapshot);
+                        events.into_iter().map(Ok).collect()
+                    }
+                    Err(e) => vec![Err(e)],
+                };
+
+                for event in events {
+                    if sender.send(event).is_err() {
+                        return;
+                    }
+                }
+                thread::park_timeout(self.interval);
+            }
+        });
+
+        let handle = WatchHandle {
+            stopped,
+            thread: Some(thread),
+        };
+        (receiver, handle)
+    }
+}
+
+/// Stops the background thread of a [`WindowWatcher`](struct.WindowWatcher.html) when stopped or dropped.
+#[derive(Debug)]
+pub struct WatchHandle {
+    stopped: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl WatchHandle {
+    /// Stop watching and wait for the background thread to finish. No more events are sent afterwards.
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            thread.thread().unpark();
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for WatchHandle {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// The state of the watched windows at one point in time.
+#[derive(Debug, Default)]
+struct Snapshot {
+    windows: Vec<WindowInfo>,
+    active: Option<WindowId>,
+}
+
+impl Snapshot {
+    fn take(filter: &SearchQuery) -> Result<Snapshot> {
+        let windows = window::list_all(filter)?;
+        // Without an active window, e.g. on an empty desktop, xdotool fails
+        let active = stdout(desktop::get_active_window())
+            .ok()
+            .and_then(|id| id.parse().ok());
+        Ok(Snapshot { windows, active })
+    }
+}
+
+/// The events turning `old` into `new`, in the order of the windows in `new`.
+fn diff(old: &Snapshot, new: &Snapshot) -> Vec<WindowEvent> {
+    let known: HashMap<WindowId, &WindowInfo> = old.windows.iter().map(|w| (w.id, w)).collect();
+    let current: HashMap<WindowId, &WindowInfo> = new.windows.iter().map(|w| (w.id, w)).collect();
+    let mut events = Vec::new();
+
+    for window in &new.windows {
+        let id = window.id;
+        let old = match known.get(&id) {
+            Some(old) => old,
+            None => {
+                events.push(WindowEvent::Created(window.clone()));
+                continue;
+            }
+        };
+
+        if old.name != window.name {
+            events.push(WindowEvent::TitleChanged {
+                id,
+                old: old.name.clone(),
+                new: window.name.clone(),
+            });
+        }
+        if old.geometry != window.geometry {
+            events.push(WindowEvent::GeometryChanged {
+                id,
+                old: old.geometry,
+                new: window.geometry,
+            });
+        }
+        if old.desktop != window.desktop {
+            events.push(WindowEvent::DesktopChanged {
+                id,
+                old: old.desktop,
+                new: window.desktop,
+            });
+        }
+    }
+
+    events.extend(
+        old.windows
+            .iter()
+            .filter(|w| !current.contains_key(&w.id))
+            .map(|w| WindowEvent::Destroyed(w.id)),
+    );
+
+    if old.active != new.active {
+        events.push(WindowEvent::ActiveChanged {
+            old: old.active,
+            new: new.active,
+        });
+    }
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff, Snapshot, WindowEvent};
+    use crate::window::{Geometry, WindowId, WindowInfo};
+
+    fn window(id: u64, name: &str, x: i32) -> WindowInfo {
+        WindowInfo {
+            id: WindowId(id),
+            name: name.to_owned(),
+            class: "XTerm".to_owned(),
+            classname: Some("xterm".to_owned()),
+            pid: Some(42),
+            geometry: Geometry {
+                x,
+                y: 0,
+                width: 800,
+                height: 600,
+                screen: 0,
+            },
+            desktop: Some(0),
+            visible: true,
+        }
+    }
+
+    #[test]
+    fn unchanged_snapshots_have_no_events() {
+        let snapshot = Snapshot {
+            windows: vec![window(1, "vim", 0)],
+            active: Some(WindowId(1)),
+        };
+        assert!(diff(&snapshot, &snapshot).is_empty());
+    }
+
+    #[test]
+    fn changes_are_reported() {
+        let old = Snapshot {
+            windows: vec![window(1, "vim", 0), window(2, "htop", 0)],
+            active: Some(WindowId(1)),
+        };
+        let mut moved = window(1, "vim README.md", 100);
+        moved.desktop = Some(1);
+        let new = Snapshot {
+            windows: vec![moved, window(3, "bash", 0)],
+            active: Some(WindowId(3)),
+        };
+
+        let events = diff(&old, &new);
+        assert_eq!(
+            events[0],
+            WindowEvent::TitleChanged {
+                id: WindowId(1),
+                old: "vim".to_owned(),
+                new: "vim README.md".to_owned(),
+            }
+        );
+        assert!(matches!(events[1], WindowEvent::GeometryChanged { ref new, .. } if new.x == 100));
+        assert_eq!(
+            events[2],
+            WindowEvent::DesktopChanged {
+                id: WindowId(1),
+                old: Some(0),
+                new: Some(1),
+            }
+        );
+        assert!(matches!(events[3], WindowEvent::Created(ref w) if w.id == WindowId(3)));
+        assert_eq!(events[4], WindowEvent::Destroyed(WindowId(2)));
+        assert_eq!(
+            events[5],
+            WindowEvent::ActiveChanged {
+                old: Some(WindowId(1)),
+                new: Some(WindowId(3)),
+            }
+        );
+        assert_eq!(events.len(), 6);
+    }
+}
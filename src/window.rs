@@ -1,13 +1,27 @@
 //! Convenience functions for the window functionality in xdotool.
 
+use crate::chain::Chain;
 use crate::command::options::{
     GetWindowGeometryOption, SearchOption, SetWindowOption, SyncOption, WindowMoveOption,
-    WindowSizeOption,
+    WindowSizeOption, WindowStateOption,
 };
 use crate::command::{sub_commands, Command};
+use crate::delivery::Delivery;
+use crate::error::{Error, Result};
+use crate::mouse::{Button, Location};
 use crate::optionvec::OptionVec;
-use crate::run;
-use std::process::Output;
+use crate::search::SearchQuery;
+use crate::wait;
+use crate::{desktop, keyboard, mouse, option_vec, quote, run, stdout};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io::{self, BufRead};
+use std::process::{self, Output};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
 
 // TODO: implement the behave command
 
@@ -21,6 +35,8 @@ use std::process::Output;
 /// - `SearchOption::ClassName` Match against the window class name.
 /// - `SearchOption::MaxDepth(i32)` Set the recursion/child search depth. Default is -1, meaning infinite. 0 means no depth, only root windows will be searched.
 /// - `SearchOption::Name` Match against the window name. This is the same string that is displayed in the window title bar.
+/// - `SearchOption::Role` Match against the window role (WM_WINDOW_ROLE).
+/// - `SearchOption::Title` Deprecated alias of `SearchOption::Name` kept by xdotool for compatibility.
 /// - `SearchOption::OnlyVisible` Show only visible windows in the results. 
 /// - `SearchOption::Pid(u32)` Match windows that belong to a specific process id.
 /// - `SearchOption::Screen(u8)` Only match windows on a certain desktop. The default is to search all desktops.
@@ -30,6 +46,8 @@ use std::process::Output;
 /// - `SearchOption::Any` Match windows that match any condition. This is on by default.
 /// - `SearchOption::Sync` Block until there are results. 
 /// 
+/// Use a [`SearchQuery`](../search/struct.SearchQuery.html) to validate the pattern and get typed results.
+///
 /// # Examples
 /// 
 /// Search for window on desktop 2: 
@@ -61,6 +79,12 @@ pub fn get_window_name(window: &str) -> Output {
     run(c, window)
 }
 
+/// Output the class of a given window, e.g. `firefox`. This requires xdotool 3.20210804 or newer.
+pub fn get_window_class_name(window: &str) -> Output {
+    let c = Command::Window(sub_commands::Window::GetWindowClassName);
+    run(c, window)
+}
+
 /// Output the geometry (location and position) of a window.
 /// The values include: `x`, `y`, `width`, `height` and `screen number`.
 /// 
@@ -79,86 +103,105 @@ pub fn get_window_focus(window: &str) -> Output {
 }
 
 /// Set the window size of the given window.
-/// 
-/// 
-/// Percentages are valid for `width` and `height`. 
-/// They are relative to the geometry of the screen the window is on.
+///
+/// Use `Length::Percent` to size relative to the geometry of the screen the window is on
+/// and `Length::Keep` to leave a dimension unchanged.
+/// If either dimension is given in `Length::Cells`, `WindowSizeOption::UseHints` is added automatically.
+/// Cells should not be mixed with pixels or percentages, since xdotool applies the hints to both dimensions.
 ///
 /// # Options
-/// 
+///
 /// - `WindowSizeOption::UseHints` Use window sizing hints to set width and height.
 /// - `WindowSizeOption::Sync` Wait until the window is actually resized.
-/// 
+///
 /// # Examples
-/// 
+///
 /// Set a terminal to be 80x24 character:
-/// 
+///
 /// ```
-/// # use xdotool::{window, option_vec, OptionVec};
-/// # use xdotool::command::options;
-/// let output = window::set_window_size("terminal-id", "80", "24", option_vec![
-///     options::WindowSizeOption::UseHints,
-/// ]);
+/// # use xdotool::{window, OptionVec};
+/// # use xdotool::window::Length;
+/// let output = window::set_window_size("terminal-id", Length::Cells(80), Length::Cells(24), OptionVec::new());
 /// ```
-/// 
+///
 /// Make a window full height but half width:
-/// 
+///
 /// ```
-/// # use xdotool::{window, option_vec, OptionVec};
-/// # use xdotool::command::options;
-/// let output = window::set_window_size("window-id", "50%", "100%", OptionVec::new());
+/// # use xdotool::{window, OptionVec};
+/// # use xdotool::window::Length;
+/// let output = window::set_window_size("window-id", Length::Percent(50), Length::Percent(100), OptionVec::new());
 /// ```
 pub fn set_window_size(
     window: &str,
-    width: &str,
-    height: &str,
-    options: OptionVec<WindowSizeOption>,
+    width: Length,
+    height: Length,
+    mut options: OptionVec<WindowSizeOption>,
 ) -> Output {
+    let uses_cells = matches!(width, Length::Cells(_)) || matches!(height, Length::Cells(_));
+    let has_hints = options
+        .0
+        .iter()
+        .any(|o| matches!(o, WindowSizeOption::UseHints));
+    if uses_cells && !has_hints {
+        options.0.push(WindowSizeOption::UseHints);
+    }
+
     let c = Command::Window(sub_commands::Window::WindowSize(options));
-    let args = format!("{} {} {}", window, width, height);
+    let args = format!("{} {} {}", window, width.to_arg("x"), height.to_arg("y"));
 
     run(c, &args)
 }
 
 /// Move the window to the given position.
-/// 
-/// 
-/// If the given x coordinate is literally 'x', then the window's current x position will be unchanged.
-/// The same applies for 'y'.
-/// Percentages are valid width and height.
-/// They are relative to the geometry of the screen the window is on.
-/// 
+///
+/// Use `Length::Keep` to leave the x or y position unchanged.
+/// `Length::Percent` is relative to the geometry of the screen the window is on.
+/// `Length::Cells` has no meaning for positions and is treated like `Length::Px`.
+///
 /// # Options
-/// 
+///
 /// - `WindowMoveOption::Sync` Wait until the window is actually moved.
 /// - `WindowMoveOption::Relative` Make movement relative to the current window position.
-/// 
+///
 /// # Examples
-/// 
-/// Align window to the right of the screen: 
-/// 
+///
+/// Align window to the right of the screen:
+///
 /// ```
-/// # use xdotool::{window, option_vec, OptionVec};
-/// # use xdotool::command::options;
-/// let output = window::move_window("window-id", "50%", "0", OptionVec::new());
+/// # use xdotool::{window, OptionVec};
+/// # use xdotool::window::Length;
+/// let output = window::move_window("window-id", Length::Percent(50), Length::Px(0), OptionVec::new());
 /// ```
-pub fn move_window(window: &str, x: &str, y: &str, options: OptionVec<WindowMoveOption>) -> Output {
+///
+/// Move a window to the top of the screen without changing its x position:
+///
+/// ```
+/// # use xdotool::{window, OptionVec};
+/// # use xdotool::window::Length;
+/// let output = window::move_window("window-id", Length::Keep, Length::Px(0), OptionVec::new());
+/// ```
+pub fn move_window(
+    window: &str,
+    x: Length,
+    y: Length,
+    options: OptionVec<WindowMoveOption>,
+) -> Output {
     let c = Command::Window(sub_commands::Window::WindowMove(options));
-    let args = format!("{} {} {}", window, x, y);
+    let args = format!("{} {} {}", window, x.to_arg("x"), y.to_arg("y"));
 
     run(c, &args)
 }
 
-
 /// Focus a window (May be ignored by some window managers or programs).
-/// 
-
+///
+/// # Options
+///
+/// - `SyncOption::Sync` Wait until the window is actually focused.
 pub fn focus_window(window: &str, options: OptionVec<SyncOption>) -> Output {
     let c = Command::Window(sub_commands::Window::WindowFocus(options));
     run(c, window)
 }
 
-
 /// Map a window. In X11 terminology, mapping a window means making it visible to the screen. 
 /// 
 /// # Options
@@ -199,6 +242,13 @@ pub fn close_window(window: &str) -> Output {
     run(c, window)
 }
 
+/// Ask a window to close, like its close button does, by sending `_NET_CLOSE_WINDOW` to the window manager.
+/// The application may ask for confirmation first or refuse to close.
+pub fn quit_window(window: &str) -> Output {
+    let c = Command::Window(sub_commands::Window::WindowQuit);
+    run(c, window)
+}
+
 /// Kill a window. This action will destroy the window and kill the client controlling it.
 pub fn kill_window(window: &str) -> Output {
     let c = Command::Window(sub_commands::Window::WindowKill);
@@ -231,3 +281,1294 @@ pub fn set_window(window: &str, options: OptionVec<SetWindowOption>) -> Output {
     let c = Command::Window(sub_commands::Window::SetWindow(options));
     run(c, window)
 }
+
+/// Change the `_NET_WM_STATE` properties of a window. Requires xdotool 3.20210804 or newer.
+///
+/// # Options
+///
+/// - `WindowStateOption::Add(String)` Add a property, e.g. `ABOVE`.
+/// - `WindowStateOption::Remove(String)` Remove a property.
+/// - `WindowStateOption::Toggle(String)` Add the property if it isn't set, otherwise remove it.
+///
+/// Use [`set_state`](fn.set_state.html) for typed properties and a fallback for older versions of xdotool.
+pub fn window_state(window: &str, options: OptionVec<WindowStateOption>) -> Output {
+    let c = Command::Window(sub_commands::Window::WindowState(options));
+    run(c, window)
+}
+
+/// The X window identifier as printed by xdotool.
+///
+/// # Examples
+///
+/// ```
+/// # use xdotool::window::WindowId;
+/// let id: WindowId = "62914567".parse()?;
+/// assert_eq!(id.to_string(), "62914567");
+/// # Ok::<(), xdotool::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WindowId(pub u64);
+
+impl fmt::Display for WindowId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for WindowId {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        s.trim()
+            .parse()
+            .map(WindowId)
+            .map_err(|_| Error::Parse(format!("invalid window id '{}'", s.trim())))
+    }
+}
+
+/// A size or position argument for [`set_window_size`](fn.set_window_size.html) and [`move_window`](fn.move_window.html).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Length {
+    /// An absolute value in pixels.
+    Px(i32),
+    /// A percentage of the geometry of the screen the window is on. xdotool only accepts whole percentages.
+    Percent(u32),
+    /// Keep the window's current value.
+    Keep,
+    /// A number of character cells, e.g. for terminals. Requires `WindowSizeOption::UseHints`.
+    Cells(u32),
+}
+
+impl Length {
+    /// Render the argument the way xdotool parses it.
+    /// `keep` is the literal xdotool expects for an unchanged value on this axis ("x" or "y").
+    fn to_arg(self, keep: &str) -> String {
+        match self {
+            Length::Px(x) => x.to_string(),
+            Length::Percent(x) => format!("{}%", x),
+            Length::Keep => keep.to_owned(),
+            Length::Cells(x) => x.to_string(),
+        }
+    }
+}
+
+/// The geometry of a window as reported by [`get_window_geometry`](fn.get_window_geometry.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Geometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub screen: u8,
+}
+
+impl FromStr for Geometry {
+    type Err = Error;
+
+    /// Parse the output of `getwindowgeometry --shell`.
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || Error::Parse(format!("invalid window geometry '{}'", s.trim()));
+        let (mut x, mut y, mut width, mut height, mut screen) = (None, None, None, None, None);
+
+        for line in s.lines() {
+            let mut kv = line.splitn(2, '=');
+            match (kv.next(), kv.next()) {
+                (Some("X"), Some(v)) => x = v.parse().ok(),
+                (Some("Y"), Some(v)) => y = v.parse().ok(),
+                (Some("WIDTH"), Some(v)) => width = v.parse().ok(),
+                (Some("HEIGHT"), Some(v)) => height = v.parse().ok(),
+                (Some("SCREEN"), Some(v)) => screen = v.parse().ok(),
+                _ => {}
+            }
+        }
+
+        Ok(Geometry {
+            x: x.ok_or_else(invalid)?,
+            y: y.ok_or_else(invalid)?,
+            width: width.ok_or_else(invalid)?,
+            height: height.ok_or_else(invalid)?,
+            screen: screen.ok_or_else(invalid)?,
+        })
+    }
+}
+
+/// A handle to a window that offers all window operations as methods with typed results.
+///
+/// Keyboard and mouse input is delivered as described by the window's [`Delivery`](../delivery/enum.Delivery.html).
+///
+/// # Examples
+///
+/// Move the active window to the left half of the screen and rename it:
+///
+/// ```no_run
+/// # use xdotool::window::{Length, Window};
+/// let window = Window::active()?;
+/// window.move_to(Length::Px(0), Length::Px(0))?;
+/// window.resize(Length::Percent(50), Length::Percent(100))?;
+/// window.set_name(&format!("{} (left)", window.name()?))?;
+/// # Ok::<(), xdotool::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Window {
+    id: WindowId,
+    delivery: Delivery,
+    restore_focus: bool,
+}
+
+impl Window {
+    /// Create a handle using the default [`Delivery`](../delivery/enum.Delivery.html).
+    pub fn new(id: WindowId) -> Window {
+        Window {
+            id,
+            delivery: Delivery::default(),
+            restore_focus: false,
+        }
+    }
+
+    /// The currently active window.
+    pub fn active() -> Result<Window> {
+        Ok(Window::new(stdout(desktop::get_active_window())?.parse()?))
+    }
+
+    /// Set how keyboard and mouse input is delivered to the window.
+    pub fn with_delivery(mut self, delivery: Delivery) -> Window {
+        self.delivery = delivery;
+        self
+    }
+
+    /// Activate the previously active window again after input was delivered by changing the focus.
+    pub fn with_restore_focus(mut self, restore_focus: bool) -> Window {
+        self.restore_focus = restore_focus;
+        self
+    }
+
+    pub fn id(&self) -> WindowId {
+        self.id
+    }
+
+    /// The name of the window, also known as the title.
+    pub fn name(&self) -> Result<String> {
+        let name = stdout(get_window_name(&self.arg()))?;
+        Ok(name.strip_suffix('\n').unwrap_or(&name).to_owned())
+    }
+
+    /// The class of the window.
+    pub fn class(&self) -> Result<String> {
+        Ok(stdout(get_window_class_name(&self.arg()))?
+            .trim()
+            .to_owned())
+    }
+
+    /// The pid owning the window. Not every application sets it.
+    pub fn pid(&self) -> Result<u32> {
+        let pid = stdout(get_window_pid(&self.arg()))?;
+        pid.trim()
+            .parse()
+            .map_err(|_| Error::Parse(format!("invalid pid '{}'", pid.trim())))
+    }
+
+    pub fn geometry(&self) -> Result<Geometry> {
+        let c = option_vec![GetWindowGeometryOption::Shell];
+        stdout(get_window_geometry(&self.arg(), c))?.parse()
+    }
+
+    /// See [`move_window`](fn.move_window.html). Waits until the window is actually moved.
+    pub fn move_to(&self, x: Length, y: Length) -> Result<()> {
+        let options = option_vec![WindowMoveOption::Sync];
+        stdout(move_window(&self.arg(), x, y, options)).map(|_| ())
+    }
+
+    /// See [`set_window_size`](fn.set_window_size.html). Waits until the window is actually resized.
+    pub fn resize(&self, width: Length, height: Length) -> Result<()> {
+        let options = option_vec![WindowSizeOption::Sync];
+        stdout(set_window_size(&self.arg(), width, height, options)).map(|_| ())
+    }
+
+    pub fn focus(&self) -> Result<()> {
+        stdout(focus_window(&self.arg(), option_vec![SyncOption::Sync])).map(|_| ())
+    }
+
+    /// Activate the window, switching to its desktop if necessary.
+    pub fn activate(&self) -> Result<()> {
+        let options = option_vec![SyncOption::Sync];
+        stdout(desktop::activate_window(&self.arg(), options)).map(|_| ())
+    }
+
+    pub fn raise(&self) -> Result<()> {
+        stdout(raise_window(&self.arg())).map(|_| ())
+    }
+
+    pub fn minimize(&self) -> Result<()> {
+        stdout(minimize_window(&self.arg(), option_vec![SyncOption::Sync])).map(|_| ())
+    }
+
+    pub fn map(&self) -> Result<()> {
+        stdout(window_map(&self.arg(), option_vec![SyncOption::Sync])).map(|_| ())
+    }
+
+    pub fn unmap(&self) -> Result<()> {
+        stdout(unmap_window(&self.arg(), option_vec![SyncOption::Sync])).map(|_| ())
+    }
+
+    /// Destroy the window without killing the client controlling it.
+    pub fn close(&self) -> Result<()> {
+        stdout(close_window(&self.arg())).map(|_| ())
+    }
+
+    /// Destroy the window and kill the client controlling it.
+    pub fn kill(&self) -> Result<()> {
+        stdout(kill_window(&self.arg())).map(|_| ())
+    }
+
+    /// See [`set_state`](fn.set_state.html).
+    pub fn set_state(&self, change: StateChange, state: WindowState) -> Result<()> {
+        set_state(self.id, change, state)
+    }
+
+    /// See [`get_states`](fn.get_states.html).
+    pub fn states(&self) -> Result<HashSet<WindowState>> {
+        get_states(self.id)
+    }
+
+    /// Close the window gracefully, see [`shutdown`](fn.shutdown.html).
+    pub fn shutdown(&self, policy: &ShutdownPolicy) -> Result<ShutdownReport> {
+        shutdown(self.id, policy)
+    }
+
+    /// Set the name of the window, usually the title.
+    pub fn set_name(&self, name: &str) -> Result<()> {
+        let options = option_vec![SetWindowOption::Name(quote(name))];
+        stdout(set_window(&self.arg(), options)).map(|_| ())
+    }
+
+    /// The desktop containing the window.
+    pub fn desktop(&self) -> Result<u32> {
+        let desktop = stdout(desktop::get_desktop_for_window(&self.arg()))?;
+        desktop
+            .trim()
+            .parse()
+            .map_err(|_| Error::Parse(format!("invalid desktop '{}'", desktop.trim())))
+    }
+
+    pub fn move_to_desktop(&self, desktop_number: u8) -> Result<()> {
+        stdout(desktop::set_desktop_for_window(&self.arg(), desktop_number)).map(|_| ())
+    }
+
+    /// Send keystrokes, see [`keyboard::send_key`](../keyboard/fn.send_key.html).
+    pub fn send_keys(&self, keys: &str) -> Result<()> {
+        keyboard::send_key_to(
+            &self.arg(),
+            keys,
+            OptionVec::new(),
+            &self.delivery,
+            self.restore_focus,
+        )
+    }
+
+    /// Type `text` and return the number of characters typed, see [`keyboard::TypeEngine`](../keyboard/struct.TypeEngine.html).
+    pub fn type_text(&self, text: &str) -> Result<usize> {
+        keyboard::type_text_to(
+            &self.arg(),
+            text,
+            OptionVec::new(),
+            &self.delivery,
+            self.restore_focus,
+        )
+    }
+
+    /// Click at the current mouse position.
+    pub fn click(&self, button: Button) -> Result<()> {
+        mouse::click_to(
+            &self.arg(),
+            button,
+            OptionVec::new(),
+            &self.delivery,
+            self.restore_focus,
+        )
+    }
+
+    fn arg(&self) -> String {
+        self.id.to_string()
+    }
+}
+
+impl fmt::Display for Window {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.id)
+    }
+}
+
+/// A snapshot of a window and its metadata, see [`list_all`](fn.list_all.html).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WindowInfo {
+    pub id: WindowId,
+    pub name: String,
+    /// The class of WM_CLASS, matched by `SearchOption::Class`.
+    pub class: String,
+    /// The instance name of WM_CLASS, matched by `SearchOption::ClassName`. Requires `xwininfo`.
+    pub classname: Option<String>,
+    /// Not every application sets the pid of its windows.
+    pub pid: Option<u32>,
+    pub geometry: Geometry,
+    /// Windows that are not managed by the window manager have no desktop.
+    pub desktop: Option<u32>,
+    pub visible: bool,
+}
+
+/// List all windows matching `filter` with their metadata.
+///
+/// The metadata of all windows is gathered by a single chained xdotool process using the window stack (`%@`),
+/// instead of one process per window and property.
+/// A window without pid or desktop makes its query fail, which aborts the chain. That value is `None`
+/// and the remaining queries continue in a new chain, so every such window costs one more process.
+/// The instance names are read with a single call of `xwininfo` and the visibility with a second search.
+///
+/// With the `serde` feature [`WindowInfo`](struct.WindowInfo.html) can be serialized.
+///
+/// # Examples
+///
+/// List all visible windows on desktop 0:
+///
+/// ```no_run
+/// # use xdotool::search::SearchQuery;
+/// # use xdotool::window;
+/// for window in window::list_all(&SearchQuery::new().desktop(0).only_visible())? {
+///     println!("{} {} ({})", window.id, window.name, window.class);
+/// }
+/// # Ok::<(), xdotool::Error>(())
+/// ```
+pub fn list_all(filter: &SearchQuery) -> Result<Vec<WindowInfo>> {
+    let mut searches = filter.searches(true)?;
+
+    // A single search fills the window stack, otherwise the combined results are queried by id
+    let (chain, targets) = if searches.len() == 1 {
+        let (pattern, options) = searches.remove(0);
+        let chain = Chain::new().then(
+            Command::Window(sub_commands::Window::Search(options)),
+            &quote(&pattern),
+        );
+        (chain, vec!["%@".to_owned()])
+    } else {
+        let ids = filter.run()?;
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        (Chain::new(), ids.iter().map(WindowId::to_string).collect())
+    };
+
+    let queries = [
+        Command::Window(sub_commands::Window::GetWindowGeometry(option_vec![
+            GetWindowGeometryOption::Shell
+        ])),
+        Command::Window(sub_commands::Window::GetWindowName),
+        Command::Window(sub_commands::Window::GetWindowClassName),
+        Command::Desktop(sub_commands::Desktop::GetDesktopForWindow),
+        Command::Window(sub_commands::Window::GetWindowPid),
+    ];
+    let chain = queries.iter().fold(chain, |chain, command| {
+        targets
+            .iter()
+            .fold(chain, |chain, target| chain.then(command.clone(), target))
+    });
+
+    let output = chain.run();
+    if output.status.code() == Some(1) && output.stdout.is_empty() && output.stderr.is_empty() {
+        // The search found nothing
+        return Ok(Vec::new());
+    }
+    let listing = parse_listing(&String::from_utf8_lossy(&output.stdout))?;
+    if listing.names.len() < listing.windows.len() || listing.classes.len() < listing.windows.len()
+    {
+        // A window vanished while it was queried
+        stdout(output)?;
+        return Err(Error::Parse("incomplete window listing".to_owned()));
+    }
+
+    let visible: HashSet<WindowId> = filter.clone().only_visible().run()?.into_iter().collect();
+    let classnames = instance_names();
+
+    let Listing {
+        windows,
+        names,
+        classes,
+        desktops,
+        pids,
+    } = listing;
+
+    // The desktops and then the pids, by id since a new chain has no window stack
+    let properties: Vec<(&Command, String)> = queries[3..]
+        .iter()
+        .flat_map(|command| windows.iter().map(move |(id, _)| (command, id.to_string())))
+        .collect();
+    let received = desktops.into_iter().chain(pids).collect();
+    let values = resume_queries(received, properties.len(), |start| {
+        let chain = properties[start..]
+            .iter()
+            .fold(Chain::new(), |chain, (command, target)| {
+                chain.then((*command).clone(), target)
+            });
+        String::from_utf8_lossy(&chain.run().stdout)
+            .lines()
+            .map(str::to_owned)
+            .collect()
+    });
+    let parse = |value: &Option<String>| value.as_ref().and_then(|x| x.trim().parse().ok());
+    let (desktops, pids) = values.split_at(windows.len());
+
+    Ok(windows
+        .into_iter()
+        .zip(names.into_iter().zip(classes))
+        .enumerate()
+        .map(|(i, ((id, geometry), (name, class)))| WindowInfo {
+            id,
+            name,
+            class,
+            classname: classnames.as_ref().and_then(|c| c.get(&id).cloned()),
+            pid: parse(&pids[i]),
+            geometry,
+            desktop: parse(&desktops[i]),
+            visible: visible.contains(&id),
+        })
+        .collect())
+}
+
+/// Complete the values of `total` chained queries of one line each, of which the first ones were `received`.
+///
+/// A failing query aborts the chain, so it has no value and `run` continues the chain with the query after it.
+/// `run` gets the index of the first query to run and returns the printed lines.
+fn resume_queries<F>(received: Vec<String>, total: usize, mut run: F) -> Vec<Option<String>>
+where
+    F: FnMut(usize) -> Vec<String>,
+{
+    let mut values: Vec<Option<String>> = received.into_iter().map(Some).collect();
+    while values.len() < total {
+        // The query that aborted the chain
+        values.push(None);
+        if values.len() < total {
+            let start = values.len();
+            values.extend(run(start).into_iter().take(total - start).map(Some));
+        }
+    }
+    values
+}
+
+/// The output of the chained queries of [`list_all`](fn.list_all.html), grouped by query.
+#[derive(Debug, Default, PartialEq)]
+struct Listing {
+    windows: Vec<(WindowId, Geometry)>,
+    names: Vec<String>,
+    classes: Vec<String>,
+    desktops: Vec<String>,
+    pids: Vec<String>,
+}
+
+/// Split the output of the chained queries, which may end early.
+/// Every geometry spans six lines starting with `WINDOW=`, every other query prints one line per window.
+fn parse_listing(output: &str) -> Result<Listing> {
+    let mut lines = output.lines().peekable();
+    let mut listing = Listing::default();
+
+    while let Some(line) = lines.next_if(|l| l.starts_with("WINDOW=")) {
+        let block: Vec<&str> = std::iter::once(line)
+            .chain(lines.by_ref().take(5))
+            .collect();
+        let id = line["WINDOW=".len()..].parse()?;
+        listing.windows.push((id, block.join("\n").parse()?));
+    }
+
+    let n = listing.windows.len();
+    for values in [
+        &mut listing.names,
+        &mut listing.classes,
+        &mut listing.desktops,
+        &mut listing.pids,
+    ] {
+        values.extend(lines.by_ref().take(n).map(str::to_owned));
+    }
+    Ok(listing)
+}
+
+/// The instance names of WM_CLASS of all windows, or `None` if `xwininfo` is not available.
+fn instance_names() -> Option<HashMap<WindowId, String>> {
+    let output = process::Command::new("xwininfo")
+        .args(["-root", "-tree"])
+        .output()
+        .ok()?;
+    Some(parse_instance_names(&stdout(output).ok()?))
+}
+
+/// Parse lines like `0x3a00007 "title": ("instance" "Class")  800x600+0+0  +10+20` of `xwininfo -tree`.
+fn parse_instance_names(tree: &str) -> HashMap<WindowId, String> {
+    tree.lines()
+        .filter_map(|line| {
+            let hex = line.split_whitespace().next()?.strip_prefix("0x")?;
+            let id = WindowId(u64::from_str_radix(hex, 16).ok()?);
+            let (_, class) = line.rsplit_once(": (\"")?;
+            let (instance, _) = class.split_once("\" \"")?;
+            Some((id, instance.to_owned()))
+        })
+        .collect()
+}
+
+/// How a window is asked to close by [`shutdown`](fn.shutdown.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloseRequest {
+    /// Ask the window manager to close the window with `_NET_CLOSE_WINDOW` through `windowquit`, like its close button.
+    /// Older versions of xdotool don't know `windowquit`, then `wmctrl -c` is used if it is installed.
+    WindowQuit,
+    /// Activate the window and press `alt+F4`, for applications that only ask to save their work this way.
+    AltF4,
+}
+
+/// The escalation of [`shutdown`](fn.shutdown.html).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShutdownPolicy {
+    pub close: CloseRequest,
+    /// How long to wait for the window to disappear after each step.
+    pub grace: Duration,
+    /// Send SIGTERM to the process owning the window if it is still open.
+    pub terminate: bool,
+    /// Finally destroy the window and kill its client with `windowkill`.
+    pub kill: bool,
+    /// Only report the steps that would be taken.
+    pub dry_run: bool,
+}
+
+impl Default for ShutdownPolicy {
+    fn default() -> Self {
+        ShutdownPolicy {
+            close: CloseRequest::WindowQuit,
+            grace: Duration::from_secs(5),
+            terminate: true,
+            kill: true,
+            dry_run: false,
+        }
+    }
+}
+
+/// A step of [`shutdown`](fn.shutdown.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShutdownAction {
+    Close(CloseRequest),
+    /// Send SIGTERM to the pid, or `None` if the window has no pid.
+    Terminate(Option<u32>),
+    Kill,
+}
+
+/// What happened after a step of [`shutdown`](fn.shutdown.html).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StepOutcome {
+    /// The window disappeared.
+    Closed,
+    /// The window was still open after the grace period.
+    StillOpen,
+    /// The step couldn't be taken, e.g. because the window has no pid.
+    Skipped,
+    /// The step failed with this error, e.g. because the window manager doesn't support `_NET_CLOSE_WINDOW`.
+    Failed(String),
+    /// The step would be taken, but this is a dry run.
+    Planned,
+}
+
+/// The steps taken by [`shutdown`](fn.shutdown.html) in order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShutdownReport {
+    pub steps: Vec<(ShutdownAction, StepOutcome)>,
+}
+
+impl ShutdownReport {
+    /// Whether the window is gone.
+    pub fn closed(&self) -> bool {
+        self.steps
+            .last()
+            .is_some_and(|(_, outcome)| *outcome == StepOutcome::Closed)
+    }
+}
+
+/// Close a window gracefully and escalate until it is gone.
+///
+/// First the window is asked to close, then its process receives SIGTERM and finally it is killed with `windowkill`.
+/// After each step there is a grace period for the window to disappear; the remaining steps are skipped once it did.
+/// A failing step is reported as `StepOutcome::Failed` and the next step is tried.
+///
+/// # Examples
+///
+/// Review the steps first, then close the window asking for confirmation dialogs with `alt+F4`:
+///
+/// ```no_run
+/// # use xdotool::window::{self, CloseRequest, ShutdownPolicy, WindowId};
+/// let window: WindowId = "62914567".parse()?;
+/// let mut policy = ShutdownPolicy {
+///     close: CloseRequest::AltF4,
+///     dry_run: true,
+///     ..ShutdownPolicy::default()
+/// };
+/// println!("{:?}", window::shutdown(window, &policy)?.steps);
+///
+/// policy.dry_run = false;
+/// assert!(window::shutdown(window, &policy)?.closed());
+/// # Ok::<(), xdotool::Error>(())
+/// ```
+pub fn shutdown(window: WindowId, policy: &ShutdownPolicy) -> Result<ShutdownReport> {
+    // The pid has to be known before the window is gone
+    let pid = stdout(get_window_pid(&window.to_string()))
+        .ok()
+        .and_then(|pid| pid.trim().parse().ok())
+        .filter(|pid| *pid != process::id());
+
+    Ok(run_shutdown(policy, pid, |action| {
+        shutdown_step(window, action, policy.grace)
+    }))
+}
+
+/// Take the steps of `policy` with `run_step` until one closes the window. Errors of a step are recorded.
+fn run_shutdown<F>(policy: &ShutdownPolicy, pid: Option<u32>, mut run_step: F) -> ShutdownReport
+where
+    F: FnMut(ShutdownAction) -> Result<StepOutcome>,
+{
+    let mut steps = Vec::new();
+    for action in shutdown_plan(policy, pid) {
+        if policy.dry_run {
+            steps.push((action, StepOutcome::Planned));
+            continue;
+        }
+
+        let outcome = run_step(action).unwrap_or_else(|e| StepOutcome::Failed(e.to_string()));
+        let closed = outcome == StepOutcome::Closed;
+        steps.push((action, outcome));
+        if closed {
+            break;
+        }
+    }
+    ShutdownReport { steps }
+}
+
+/// Take a step of [`shutdown`](fn.shutdown.html) and wait for the window to disappear.
+fn shutdown_step(window: WindowId, action: ShutdownAction, grace: Duration) -> Result<StepOutcome> {
+    match action {
+        ShutdownAction::Close(CloseRequest::WindowQuit) => request_close(window)?,
+        ShutdownAction::Close(CloseRequest::AltF4) => {
+            Window::new(window)
+                .with_delivery(Delivery::ActivateThenXTest)
+                .send_keys("alt+F4")?;
+        }
+        ShutdownAction::Terminate(None) => return Ok(StepOutcome::Skipped),
+        ShutdownAction::Terminate(Some(pid)) => {
+            let status = process::Command::new("kill")
+                .args(["-TERM", &pid.to_string()])
+                .status()?;
+            if !status.success() {
+                return Ok(StepOutcome::Skipped);
+            }
+        }
+        ShutdownAction::Kill => {
+            stdout(kill_window(&window.to_string()))?;
+        }
+    }
+
+    match wait::wait_until_closed(window, grace) {
+        Ok(()) => Ok(StepOutcome::Closed),
+        Err(Error::Timeout { .. }) => Ok(StepOutcome::StillOpen),
+        Err(e) => Err(e),
+    }
+}
+
+/// Send `_NET_CLOSE_WINDOW` with `windowquit`, or with `wmctrl` if xdotool doesn't know it.
+fn request_close(window: WindowId) -> Result<()> {
+    let output = quit_window(&window.to_string());
+    let unsupported = !output.status.success()
+        && String::from_utf8_lossy(&output.stderr).contains("Unknown command");
+    if !unsupported {
+        return stdout(output).map(|_| ());
+    }
+
+    let output = process::Command::new("wmctrl")
+        .args(["-i", "-c", &format!("0x{:x}", window.0)])
+        .output()?;
+    stdout(output).map(|_| ())
+}
+
+/// The steps of `policy` for a window owned by `pid`.
+fn shutdown_plan(policy: &ShutdownPolicy, pid: Option<u32>) -> Vec<ShutdownAction> {
+    let mut plan = vec![ShutdownAction::Close(policy.close)];
+    if policy.terminate {
+        plan.push(ShutdownAction::Terminate(pid));
+    }
+    if policy.kill {
+        plan.push(ShutdownAction::Kill);
+    }
+    plan
+}
+
+/// A state of a window as defined by the `_NET_WM_STATE` atoms of the EWMH specification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WindowState {
+    Modal,
+    Sticky,
+    MaximizedVert,
+    MaximizedHorz,
+    Shaded,
+    SkipTaskbar,
+    SkipPager,
+    Hidden,
+    Fullscreen,
+    Above,
+    Below,
+    DemandsAttention,
+}
+
+impl WindowState {
+    /// Both maximized states, which make up a maximized window.
+    pub const MAXIMIZED: [WindowState; 2] =
+        [WindowState::MaximizedVert, WindowState::MaximizedHorz];
+
+    const ALL: [WindowState; 12] = [
+        WindowState::Modal,
+        WindowState::Sticky,
+        WindowState::MaximizedVert,
+        WindowState::MaximizedHorz,
+        WindowState::Shaded,
+        WindowState::SkipTaskbar,
+        WindowState::SkipPager,
+        WindowState::Hidden,
+        WindowState::Fullscreen,
+        WindowState::Above,
+        WindowState::Below,
+        WindowState::DemandsAttention,
+    ];
+}
+
+impl fmt::Display for WindowState {
+    /// The name of the atom without the `_NET_WM_STATE_` prefix, as used by xdotool.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            WindowState::Modal => "MODAL",
+            WindowState::Sticky => "STICKY",
+            WindowState::MaximizedVert => "MAXIMIZED_VERT",
+            WindowState::MaximizedHorz => "MAXIMIZED_HORZ",
+            WindowState::Shaded => "SHADED",
+            WindowState::SkipTaskbar => "SKIP_TASKBAR",
+            WindowState::SkipPager => "SKIP_PAGER",
+            WindowState::Hidden => "HIDDEN",
+            WindowState::Fullscreen => "FULLSCREEN",
+            WindowState::Above => "ABOVE",
+            WindowState::Below => "BELOW",
+            WindowState::DemandsAttention => "DEMANDS_ATTENTION",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for WindowState {
+    type Err = Error;
+
+    /// Parse the name of an atom, with or without the `_NET_WM_STATE_` prefix.
+    fn from_str(s: &str) -> Result<Self> {
+        let name = s.trim();
+        let name = name.strip_prefix("_NET_WM_STATE_").unwrap_or(name);
+        WindowState::ALL
+            .iter()
+            .find(|state| state.to_string() == name)
+            .cloned()
+            .ok_or_else(|| Error::Parse(format!("unknown window state '{}'", s.trim())))
+    }
+}
+
+/// How [`set_state`](fn.set_state.html) changes a state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateChange {
+    Add,
+    Remove,
+    Toggle,
+}
+
+/// Add, remove or toggle a state of a window, e.g. to keep it above all other windows.
+///
+/// Older versions of xdotool don't know `windowstate`, then `wmctrl` is used if it is installed.
+///
+/// # Examples
+///
+/// Maximize a window:
+///
+/// ```no_run
+/// # use xdotool::window::{self, StateChange, WindowId, WindowState};
+/// let window: WindowId = "62914567".parse()?;
+/// for state in WindowState::MAXIMIZED.iter() {
+///     window::set_state(window, StateChange::Add, *state)?;
+/// }
+/// assert!(window::get_states(window)?.contains(&WindowState::MaximizedVert));
+/// # Ok::<(), xdotool::Error>(())
+/// ```
+pub fn set_state(window: WindowId, change: StateChange, state: WindowState) -> Result<()> {
+    let name = state.to_string();
+    let option = match change {
+        StateChange::Add => WindowStateOption::Add(name),
+        StateChange::Remove => WindowStateOption::Remove(name),
+        StateChange::Toggle => WindowStateOption::Toggle(name),
+    };
+
+    let output = window_state(&window.to_string(), option_vec![option]);
+    let unsupported = !output.status.success()
+        && String::from_utf8_lossy(&output.stderr).contains("Unknown command");
+    if !unsupported {
+        return stdout(output).map(|_| ());
+    }
+
+    let action = match change {
+        StateChange::Add => "add",
+        StateChange::Remove => "remove",
+        StateChange::Toggle => "toggle",
+    };
+    let output = process::Command::new("wmctrl")
+        .args(["-i", "-r", &format!("0x{:x}", window.0), "-b"])
+        .arg(format!("{},{}", action, state.to_string().to_lowercase()))
+        .output()?;
+    stdout(output).map(|_| ())
+}
+
+/// The states of a window, read from its `_NET_WM_STATE` property with `xprop`. Unknown atoms are ignored.
+pub fn get_states(window: WindowId) -> Result<HashSet<WindowState>> {
+    let output = process::Command::new("xprop")
+        .args(["-id", &window.to_string(), "_NET_WM_STATE"])
+        .output()?;
+    Ok(parse_states(&stdout(output)?))
+}
+
+/// Parse `_NET_WM_STATE(ATOM) = _NET_WM_STATE_ABOVE, _NET_WM_STATE_STICKY` as printed by `xprop`.
+/// Windows without the property print `_NET_WM_STATE:  not found.`
+fn parse_states(xprop: &str) -> HashSet<WindowState> {
+    xprop
+        .split_once('=')
+        .map(|(_, atoms)| atoms.split(',').filter_map(|a| a.parse().ok()).collect())
+        .unwrap_or_default()
+}
+
+/// A window picked by the user, see [`start_select_window`](fn.start_select_window.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Selection {
+    pub window: WindowId,
+    /// Where the user clicked.
+    pub location: Location,
+}
+
+/// Let the user pick a window by clicking on it, and wait for at most `timeout`.
+///
+/// Returns `None` if the user presses Escape or the timeout fires first.
+/// See [`start_select_window`](fn.start_select_window.html) to cancel the selection or get the click location.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use xdotool::window;
+/// match window::select_window(Duration::from_secs(30))? {
+///     Some(window) => println!("Automating window {}", window),
+///     None => println!("No window selected"),
+/// }
+/// # Ok::<(), xdotool::Error>(())
+/// ```
+pub fn select_window(timeout: Duration) -> Result<Option<WindowId>> {
+    Ok(start_select_window(timeout)?
+        .wait()?
+        .map(|selection| selection.window))
+}
+
+/// Start an interactive window selection in the background.
+///
+/// The mouse pointer turns into a crosshair until the user clicks on a window, the selection is cancelled or `timeout` fires.
+/// xdotool only grabs the pointer, so Escape is noticed by watching the raw key events with `xinput test-xi2`,
+/// which also reports keys pressed in other applications.
+/// Without `xinput` Escape is not noticed; call [`SelectCanceller::cancel`](struct.SelectCanceller.html#method.cancel),
+/// e.g. from a key handler of the own user interface, instead.
+///
+/// # Examples
+///
+/// Cancel the selection from another thread:
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use std::thread;
+/// # use xdotool::window;
+/// let handle = window::start_select_window(Duration::from_secs(60))?;
+/// let canceller = handle.canceller();
+/// thread::spawn(move || {
+///     // The user pressed "Cancel" in the setup wizard
+///     canceller.cancel();
+/// });
+///
+/// if let Some(selection) = handle.wait()? {
+///     println!("Clicked {} at {}x{}", selection.window, selection.location.x, selection.location.y);
+/// }
+/// # Ok::<(), xdotool::Error>(())
+/// ```
+pub fn start_select_window(timeout: Duration) -> Result<SelectHandle> {
+    // getwindowgeometry prints the selected window (%1) and getmouselocation where it was clicked
+    let chain = Chain::new()
+        .then(Command::Window(sub_commands::Window::SelectWindow), "")
+        .then(
+            Command::Window(sub_commands::Window::GetWindowGeometry(option_vec![
+                GetWindowGeometryOption::Shell
+            ])),
+            "",
+        )
+        .then(Command::Mouse(sub_commands::Mouse::GetMouseLocation), "");
+
+    // Spawned without a shell, so that killing the child releases the pointer grab
+    let child = process::Command::new("xdotool")
+        .args(chain.to_string().split_whitespace())
+        .stdout(process::Stdio::piped())
+        .stderr(process::Stdio::piped())
+        .spawn()?;
+
+    let cancelled = Arc::new(AtomicBool::new(false));
+    Ok(SelectHandle {
+        child: Some(child),
+        escape: watch_escape(&cancelled),
+        deadline: Instant::now() + timeout,
+        cancelled,
+    })
+}
+
+/// Set `cancelled` when Escape is pressed, by watching the raw key events with `xinput`.
+///
+/// Raw events are reported even while xdotool grabs the pointer.
+/// Returns the `xinput` process, or `None` if `xinput` or `xmodmap` is not available.
+fn watch_escape(cancelled: &Arc<AtomicBool>) -> Option<process::Child> {
+    let keymap = process::Command::new("xmodmap").arg("-pk").output().ok()?;
+    let keycodes = keycodes_of(&stdout(keymap).ok()?, ESCAPE);
+    let mut child = process::Command::new("xinput")
+        .args(["test-xi2", "--root"])
+        .stdout(process::Stdio::piped())
+        .stderr(process::Stdio::null())
+        .spawn()
+        .ok()?;
+
+    let events = io::BufReader::new(child.stdout.take()?);
+    let cancelled = Arc::clone(cancelled);
+    // The thread ends when the process is killed and its output ends
+    thread::spawn(move || {
+        if key_pressed(events, &keycodes) {
+            cancelled.store(true, Ordering::SeqCst);
+        }
+    });
+    Some(child)
+}
+
+/// The keysym of Escape, as printed by `xmodmap -pk`.
+const ESCAPE: &str = "0xff1b";
+
+/// The keycodes that produce `keysym` without modifiers, read from the output of `xmodmap -pk`.
+fn keycodes_of(keymap: &str, keysym: &str) -> HashSet<u32> {
+    keymap
+        .lines()
+        .filter_map(|line| {
+            let mut values = line.split_whitespace();
+            let keycode = values.next()?.parse().ok()?;
+            values
+                .find(|value| value.starts_with("0x"))
+                .filter(|value| *value == keysym)
+                .map(|_| keycode)
+        })
+        .collect()
+}
+
+/// Read the events printed by `xinput test-xi2` until one of `keycodes` is pressed.
+/// Returns `false` if the events end first.
+fn key_pressed<R: BufRead>(events: R, keycodes: &HashSet<u32>) -> bool {
+    let mut key_press = false;
+    for line in events.lines().map_while(|line| line.ok()) {
+        let line = line.trim();
+        if line.starts_with("EVENT type") {
+            key_press = line.ends_with("(RawKeyPress)") || line.ends_with("(KeyPress)");
+        } else if let (true, Some(detail)) = (key_press, line.strip_prefix("detail:")) {
+            if detail.trim().parse().is_ok_and(|k| keycodes.contains(&k)) {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// A running window selection. Dropping it cancels the selection.
+#[derive(Debug)]
+pub struct SelectHandle {
+    child: Option<process::Child>,
+    /// The `xinput` process watching for Escape.
+    escape: Option<process::Child>,
+    deadline: Instant,
+    cancelled: Arc<AtomicBool>,
+}
+
+/// Cancels a [`SelectHandle`](struct.SelectHandle.html) from another thread.
+#[derive(Debug, Clone)]
+pub struct SelectCanceller(Arc<AtomicBool>);
+
+impl SelectCanceller {
+    /// Cancel the selection. [`SelectHandle::wait`](struct.SelectHandle.html#method.wait) returns `None`.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+impl SelectHandle {
+    pub fn canceller(&self) -> SelectCanceller {
+        SelectCanceller(Arc::clone(&self.cancelled))
+    }
+
+    /// Wait until the user selected a window, or return `None` if the selection was cancelled or timed out.
+    pub fn wait(mut self) -> Result<Option<Selection>> {
+        let mut child = match self.child.take() {
+            Some(child) => child,
+            None => return Ok(None),
+        };
+
+        while child.try_wait()?.is_none() {
+            if self.cancelled.load(Ordering::SeqCst) || Instant::now() >= self.deadline {
+                let _ = child.kill();
+                child.wait()?;
+                return Ok(None);
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        parse_selection(&stdout(child.wait_with_output()?)?).map(Some)
+    }
+}
+
+impl Drop for SelectHandle {
+    fn drop(&mut self) {
+        for mut child in self.child.take().into_iter().chain(self.escape.take()) {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+/// Parse the output of `selectwindow getwindowgeometry --shell getmouselocation`.
+fn parse_selection(output: &str) -> Result<Selection> {
+    let invalid = || Error::Parse(format!("invalid window selection '{}'", output.trim()));
+    let window = output
+        .lines()
+        .find_map(|line| line.strip_prefix("WINDOW="))
+        .ok_or_else(invalid)?
+        .parse()?;
+    let location = output.lines().last().ok_or_else(invalid)?.parse()?;
+    Ok(Selection { window, location })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        key_pressed, keycodes_of, parse_instance_names, parse_listing, parse_selection,
+        parse_states, resume_queries, run_shutdown, shutdown_plan, CloseRequest, Geometry, Length,
+        ShutdownAction, ShutdownPolicy, StepOutcome, WindowId, WindowState, ESCAPE,
+    };
+    use crate::error::Error;
+
+    #[test]
+    fn length_renders_xdotool_syntax() {
+        assert_eq!(Length::Px(-20).to_arg("x"), "-20");
+        assert_eq!(Length::Percent(50).to_arg("x"), "50%");
+        assert_eq!(Length::Percent(100).to_arg("y"), "100%");
+        assert_eq!(Length::Keep.to_arg("x"), "x");
+        assert_eq!(Length::Keep.to_arg("y"), "y");
+        assert_eq!(Length::Cells(80).to_arg("x"), "80");
+    }
+
+    #[test]
+    fn geometry_parses_shell_output() {
+        let geometry: Geometry = "WINDOW=62914567\nX=10\nY=-20\nWIDTH=800\nHEIGHT=600\nSCREEN=0\n"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            geometry,
+            Geometry {
+                x: 10,
+                y: -20,
+                width: 800,
+                height: 600,
+                screen: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn listing_is_split_by_query() {
+        let output = "WINDOW=1\nX=0\nY=0\nWIDTH=10\nHEIGHT=20\nSCREEN=0\n\
+                      WINDOW=2\nX=5\nY=5\nWIDTH=30\nHEIGHT=40\nSCREEN=0\n\
+                      vim\nFirefox\nXTerm\nfirefox\n0\n";
+        let listing = parse_listing(output).unwrap();
+
+        let ids: Vec<WindowId> = listing.windows.iter().map(|(id, _)| *id).collect();
+        assert_eq!(ids, vec![WindowId(1), WindowId(2)]);
+        assert_eq!(listing.windows[1].1.height, 40);
+        assert_eq!(listing.names, vec!["vim", "Firefox"]);
+        assert_eq!(listing.classes, vec!["XTerm", "firefox"]);
+        // The chain stopped at the second window without a desktop
+        assert_eq!(listing.desktops, vec!["0"]);
+        assert!(listing.pids.is_empty());
+    }
+
+    #[test]
+    fn failing_queries_resume_the_chain() {
+        let output = "WINDOW=1\nX=0\nY=0\nWIDTH=10\nHEIGHT=20\nSCREEN=0\n\
+                      WINDOW=2\nX=0\nY=0\nWIDTH=10\nHEIGHT=20\nSCREEN=0\n\
+                      WINDOW=3\nX=0\nY=0\nWIDTH=10\nHEIGHT=20\nSCREEN=0\n\
+                      a\nb\nc\nA\nB\nC\n0\n";
+        let listing = parse_listing(output).unwrap();
+        assert_eq!(listing.desktops, vec!["0"]);
+        assert!(listing.pids.is_empty());
+
+        // The desktops of windows 1-3 and their pids; the desktop of window 2 and the pid of window 3 fail
+        let answers: Vec<Option<&str>> =
+            vec![Some("0"), None, Some("1"), Some("10"), Some("20"), None];
+        let mut runs = Vec::new();
+        let received = listing.desktops.into_iter().chain(listing.pids).collect();
+        let values = resume_queries(received, answers.len(), |start| {
+            runs.push(start);
+            answers[start..]
+                .iter()
+                .map_while(|answer| answer.map(str::to_owned))
+                .collect()
+        });
+
+        let expected: Vec<Option<String>> = answers.iter().map(|a| a.map(str::to_owned)).collect();
+        assert_eq!(values, expected);
+        // One more chain after the first failure, none after the last query
+        assert_eq!(runs, vec![2]);
+    }
+
+    #[test]
+    fn instance_names_are_read_from_the_tree() {
+        let tree = "  Root window id: 0x1e6 (the root window) (has no name)\n\
+                    \x20    0x3a00007 \"a \\\"b\\\": (c\": (\"xterm\" \"XTerm\")  800x600+0+0  +10+20\n\
+                    \x20       0x3a00008 (has no name): ()  1x1+0+0  +0+0\n";
+        let names = parse_instance_names(tree);
+        assert_eq!(names.len(), 1);
+        assert_eq!(names[&WindowId(0x3a00007)], "xterm");
+    }
+
+    #[test]
+    fn shutdown_escalates_as_configured() {
+        let policy = ShutdownPolicy::default();
+        assert_eq!(
+            shutdown_plan(&policy, Some(42)),
+            vec![
+                ShutdownAction::Close(CloseRequest::WindowQuit),
+                ShutdownAction::Terminate(Some(42)),
+                ShutdownAction::Kill,
+            ]
+        );
+
+        let gentle = ShutdownPolicy {
+            close: CloseRequest::AltF4,
+            terminate: false,
+            kill: false,
+            ..policy
+        };
+        assert_eq!(
+            shutdown_plan(&gentle, None),
+            vec![ShutdownAction::Close(CloseRequest::AltF4)]
+        );
+    }
+
+    #[test]
+    fn failing_steps_are_recorded_and_escalated() {
+        let policy = ShutdownPolicy::default();
+        let mut taken = Vec::new();
+        let report = run_shutdown(&policy, Some(42), |action| {
+            taken.push(action);
+            match action {
+                ShutdownAction::Close(_) => Err(Error::Failed {
+                    status: Some(1),
+                    stderr: "wmctrl: not found".to_owned(),
+                }),
+                ShutdownAction::Terminate(_) => Ok(StepOutcome::StillOpen),
+                ShutdownAction::Kill => Ok(StepOutcome::Closed),
+            }
+        });
+
+        assert_eq!(taken.len(), 3);
+        assert!(matches!(
+            &report.steps[0],
+            (ShutdownAction::Close(CloseRequest::WindowQuit), StepOutcome::Failed(reason))
+                if reason.contains("wmctrl")
+        ));
+        assert_eq!(
+            report.steps[1..],
+            [
+                (ShutdownAction::Terminate(Some(42)), StepOutcome::StillOpen),
+                (ShutdownAction::Kill, StepOutcome::Closed),
+            ]
+        );
+        assert!(report.closed());
+
+        let dry_run = ShutdownPolicy {
+            dry_run: true,
+            ..policy
+        };
+        let report = run_shutdown(&dry_run, None, |_| panic!("a dry run takes no steps"));
+        assert!(report
+            .steps
+            .iter()
+            .all(|(_, outcome)| *outcome == StepOutcome::Planned));
+    }
+
+    #[test]
+    fn window_states_round_trip() {
+        for state in WindowState::ALL.iter() {
+            assert_eq!(state.to_string().parse::<WindowState>().unwrap(), *state);
+        }
+        assert_eq!(
+            "_NET_WM_STATE_SKIP_TASKBAR".parse::<WindowState>().unwrap(),
+            WindowState::SkipTaskbar
+        );
+        assert!("_NET_WM_STATE_FOCUSED".parse::<WindowState>().is_err());
+    }
+
+    #[test]
+    fn states_are_parsed_from_xprop() {
+        let states = parse_states(
+            "_NET_WM_STATE(ATOM) = _NET_WM_STATE_MAXIMIZED_VERT, _NET_WM_STATE_FOCUSED, _NET_WM_STATE_ABOVE\n",
+        );
+        assert_eq!(states.len(), 2);
+        assert!(states.contains(&WindowState::MaximizedVert));
+        assert!(states.contains(&WindowState::Above));
+
+        assert!(parse_states("_NET_WM_STATE:  not found.\n").is_empty());
+    }
+
+    #[test]
+    fn selection_is_parsed_from_the_chain() {
+        let output = "WINDOW=62914567\nX=0\nY=0\nWIDTH=800\nHEIGHT=600\nSCREEN=0\n\
+                      x:120 y:80 screen:0 window:62914560\n";
+        let selection = parse_selection(output).unwrap();
+        assert_eq!(selection.window, WindowId(62914567));
+        assert_eq!((selection.location.x, selection.location.y), (120, 80));
+
+        assert!(parse_selection("").is_err());
+    }
+
+    #[test]
+    fn escape_is_found_in_the_raw_key_events() {
+        let keymap = concat!(
+            "      9    \t0xff1b (Escape)\t0x0000 (NoSymbol)\t0xff1b (Escape)\n",
+            "     24    \t0x0071 (q)\t0x0051 (Q)\t0xff1b (Escape)\n",
+            "     66    \t0xff1b (Escape)\n",
+        );
+        let keycodes = keycodes_of(keymap, ESCAPE);
+        assert_eq!(keycodes, [9, 66].iter().cloned().collect());
+
+        let events = "EVENT type 13 (RawKeyPress)\n    device: 3 (3)\n    detail: 24\n\
+                      EVENT type 14 (RawKeyRelease)\n    device: 3 (3)\n    detail: 9\n\
+                      EVENT type 15 (RawButtonPress)\n    device: 2 (2)\n    detail: 9\n";
+        assert!(!key_pressed(events.as_bytes(), &keycodes));
+
+        let events = "EVENT type 15 (RawButtonPress)\n    device: 2 (2)\n    detail: 1\n\
+                      EVENT type 13 (RawKeyPress)\n    device: 3 (11)\n    detail: 66\n";
+        assert!(key_pressed(events.as_bytes(), &keycodes));
+    }
+}